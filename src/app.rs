@@ -0,0 +1,394 @@
+use std::collections::HashMap;
+
+use gloo_storage::{LocalStorage, Storage};
+use gloo_worker::{Spawnable, WorkerBridge};
+use yew::prelude::*;
+
+use canvas::worker::{
+    SolveRequest, SolverOutput, SolverRequest, SolverResponse, SolverWorker, SOLVER_WORKER_PATH,
+};
+
+use super::view_components;
+use super::{archive, components, share, solver};
+
+static DEFAULT_ROWS: usize = 5;
+static DEFAULT_COLS: usize = 5;
+static SOLVER_MAX_STEPS: usize = 1_000_000;
+static SESSION_STORAGE_KEY: &str = "sichuan-solver.session";
+
+pub enum AppMsg {
+    TabChanged(view_components::main_container::MainContainerViewTarget),
+    RowsChanged(usize),
+    ColsChanged(usize),
+    RulesChanged(solver::rules::RuleSet),
+    OrderingChanged(solver::ordering::MoveOrderingKind),
+    DraftChanged(HashMap<components::Coord, components::Tile>),
+    EditorSubmitted(HashMap<components::Coord, components::Tile>),
+    EditorCleared,
+    SolverResponded(SolverResponse),
+    SolverCancelled,
+    SolutionLoaded(archive::SolutionDocument),
+}
+
+pub struct App {
+    target_view: view_components::main_container::MainContainerViewTarget,
+    rows: usize,
+    cols: usize,
+    tiles: HashMap<components::Coord, components::Tile>,
+    draft: HashMap<components::Coord, components::Tile>,
+    connections: Vec<components::Nodes>,
+    solved_rows: usize,
+    solved_cols: usize,
+    solved_rules: solver::rules::RuleSet,
+    rules: solver::rules::RuleSet,
+    ordering: solver::ordering::MoveOrderingKind,
+    failure: Option<solver::solver::SolverError>,
+    solver: WorkerBridge<SolverWorker>,
+    solving: bool,
+    solver_generation: usize,
+    progress: Option<solver::progress::SolverProgressReport>,
+    shared_step: Option<usize>,
+}
+
+impl App {
+    fn start_solving(&mut self) {
+        self.solver_generation += 1;
+        self.solver
+            .send(SolverRequest::Solve(Box::new(SolveRequest {
+                generation: self.solver_generation,
+                map: self.draft.clone(),
+                size: [self.cols, self.rows],
+                rules: self.rules,
+                path_selection: solver::connect::PathSelection::Shortest,
+                ordering: self.ordering,
+                max_steps: Some(SOLVER_MAX_STEPS),
+            })));
+        self.solving = true;
+        self.progress = None;
+    }
+
+    fn cancel_solving(&mut self) {
+        if self.solving {
+            self.solver.send(SolverRequest::Cancel);
+            self.solving = false;
+            self.progress = None;
+        }
+    }
+
+    fn save_session(&self) {
+        /*
+         * Only a complete solution is kept. It is saved with the size and
+         * rules it was solved with, since the editor may have changed both.
+         */
+        let solution = match (&self.failure, self.connections.is_empty()) {
+            (None, false) => match archive::SolutionDocument::new(
+                self.solved_rows,
+                self.solved_cols,
+                &self.tiles,
+                &self.solved_rules,
+                &self.connections,
+            ) {
+                Ok(solution) => Some(solution),
+                Err(err) => {
+                    log::warn!("Not saving the solution: {}", err);
+                    None
+                }
+            },
+            _ => None,
+        };
+        let session = archive::SessionDocument {
+            board: archive::BoardDocument::new(self.rows, self.cols, &self.draft),
+            rules: self.rules,
+            solution,
+        };
+        if LocalStorage::raw()
+            .set_item(SESSION_STORAGE_KEY, &session.to_json())
+            .is_err()
+        {
+            log::warn!("Failed to save the session.");
+        }
+    }
+
+    fn restore_session(&mut self) {
+        let json = match LocalStorage::raw().get_item(SESSION_STORAGE_KEY) {
+            Ok(Some(json)) => json,
+            _ => return,
+        };
+        let session = match archive::SessionDocument::from_json(&json) {
+            Ok(session) => session,
+            Err(err) => {
+                log::warn!("Ignoring the saved session: {}", err);
+                return;
+            }
+        };
+        self.rows = session.board.rows;
+        self.cols = session.board.cols;
+        self.draft = session.board.to_map();
+        self.rules = session.rules;
+        if let Some(solution) = session.solution {
+            self.tiles = solution.board.to_map();
+            self.connections = solution.to_nodes().unwrap_or_default();
+            self.solved_rows = solution.board.rows;
+            self.solved_cols = solution.board.cols;
+            self.solved_rules = solution.rules;
+        }
+    }
+}
+
+fn get_shared_board() -> Option<share::SharedBoard> {
+    let hash = web_sys::window()?.location().hash().ok()?;
+    if hash.trim_start_matches('#').is_empty() {
+        return None;
+    }
+    match share::SharedBoard::from_fragment(&hash) {
+        Ok(board) => Some(board),
+        Err(err) => {
+            log::warn!("Ignoring the shared board in the URL: {}", err);
+            None
+        }
+    }
+}
+
+impl Component for App {
+    type Message = AppMsg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let link = ctx.link().clone();
+        let solver = SolverWorker::spawner()
+            .callback(move |response| link.send_message(AppMsg::SolverResponded(response)))
+            .spawn(SOLVER_WORKER_PATH);
+
+        let mut app = Self {
+            target_view: view_components::main_container::MainContainerViewTarget::EditView,
+            rows: DEFAULT_ROWS,
+            cols: DEFAULT_COLS,
+            tiles: HashMap::new(),
+            draft: HashMap::new(),
+            connections: Vec::new(),
+            solved_rows: DEFAULT_ROWS,
+            solved_cols: DEFAULT_COLS,
+            solved_rules: solver::rules::RuleSet::default(),
+            rules: solver::rules::RuleSet::default(),
+            ordering: solver::ordering::MoveOrderingKind::default(),
+            failure: None,
+            solver,
+            solving: false,
+            solver_generation: 0,
+            progress: None,
+            shared_step: None,
+        };
+
+        /*
+         * A shared link takes the place of the saved session. A link that
+         * includes a step opens the result view at that step, with the moves
+         * it was shared with, or solved right away if it has none. The
+         * fragment is then dropped, so that a reload restores the edits made
+         * since.
+         */
+        match get_shared_board() {
+            Some(board) => {
+                app.rows = board.rows;
+                app.cols = board.cols;
+                app.draft = board.tiles.clone();
+                app.rules = board.rules;
+                if board.step.is_some() {
+                    app.shared_step = board.step;
+                    match board.route_moves(solver::connect::PathSelection::Shortest) {
+                        Ok(connections) if !connections.is_empty() => {
+                            app.tiles = board.tiles;
+                            app.connections = connections;
+                            app.solved_rows = board.rows;
+                            app.solved_cols = board.cols;
+                            app.solved_rules = board.rules;
+                            app.target_view =
+                                view_components::main_container::MainContainerViewTarget::ResultView;
+                        }
+                        Ok(_) => app.start_solving(),
+                        Err(err) => {
+                            log::warn!("Solving the shared board again: {}", err);
+                            app.start_solving();
+                        }
+                    }
+                }
+                if let Some(window) = web_sys::window() {
+                    let _ = window.location().set_hash("");
+                }
+                app.save_session();
+            }
+            None => app.restore_session(),
+        }
+        app
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Self::Message::TabChanged(target) => {
+                self.target_view = target;
+                true
+            }
+            Self::Message::RowsChanged(rows) => {
+                self.rows = rows;
+                self.save_session();
+                true
+            }
+            Self::Message::ColsChanged(cols) => {
+                self.cols = cols;
+                self.save_session();
+                true
+            }
+            Self::Message::RulesChanged(rules) => {
+                self.rules = rules;
+                self.save_session();
+                true
+            }
+            Self::Message::OrderingChanged(ordering) => {
+                self.ordering = ordering;
+                true
+            }
+            Self::Message::DraftChanged(tiles) => {
+                self.draft = tiles;
+                self.save_session();
+                false
+            }
+            Self::Message::EditorSubmitted(tiles) => {
+                self.draft = tiles;
+                self.shared_step = None;
+                self.start_solving();
+                true
+            }
+            Self::Message::EditorCleared => {
+                self.cancel_solving();
+                self.rows = DEFAULT_ROWS;
+                self.cols = DEFAULT_COLS;
+                self.tiles.clear();
+                self.draft.clear();
+                self.connections.clear();
+                self.solved_rows = DEFAULT_ROWS;
+                self.solved_cols = DEFAULT_COLS;
+                self.rules = solver::rules::RuleSet::default();
+                self.solved_rules = solver::rules::RuleSet::default();
+                self.failure = None;
+                self.shared_step = None;
+                LocalStorage::delete(SESSION_STORAGE_KEY);
+                true
+            }
+            Self::Message::SolverResponded(response) => {
+                if !self.solving || response.generation != self.solver_generation {
+                    return false;
+                }
+
+                let (request, result) = match response.output {
+                    SolverOutput::Progress(report) => {
+                        self.progress = Some(report);
+                        return true;
+                    }
+                    SolverOutput::Finished(request, result) => (request, result),
+                };
+
+                /*
+                 * The solver board is never smaller than the tiles, so the
+                 * result is shown at the same size.
+                 */
+                let [cols, rows] = solver::utils::get_size_from_map(&request.map);
+                self.solving = false;
+                self.progress = None;
+                self.tiles = request.map;
+                self.solved_rows = rows.max(request.size[1]);
+                self.solved_cols = cols.max(request.size[0]);
+                self.solved_rules = request.rules;
+                match result {
+                    Ok(connections) => {
+                        self.connections = connections;
+                        self.failure = None;
+                    }
+                    Err(err) => {
+                        self.connections = match err.diagnostics() {
+                            Some(diagnostics) => diagnostics.deepest.clone(),
+                            None => Vec::new(),
+                        };
+                        self.failure = Some(err);
+                    }
+                }
+                self.target_view =
+                    view_components::main_container::MainContainerViewTarget::ResultView;
+                self.save_session();
+                true
+            }
+            Self::Message::SolverCancelled => {
+                self.cancel_solving();
+                true
+            }
+            Self::Message::SolutionLoaded(solution) => {
+                self.cancel_solving();
+                self.rows = solution.board.rows;
+                self.cols = solution.board.cols;
+                self.tiles = solution.board.to_map();
+                self.draft = self.tiles.clone();
+                self.rules = solution.rules;
+                self.connections = solution.to_nodes().unwrap_or_default();
+                self.solved_rows = solution.board.rows;
+                self.solved_cols = solution.board.cols;
+                self.solved_rules = solution.rules;
+                self.failure = None;
+                self.shared_step = None;
+                self.save_session();
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <div>
+                <view_components::main_container::MainContainer
+                    target_view={self.target_view}
+                    rows={self.rows}
+                    cols={self.cols}
+                    tiles={self.tiles.clone()}
+                    draft={self.draft.clone()}
+                    connections={self.connections.to_owned()}
+                    solved_rows={self.solved_rows}
+                    solved_cols={self.solved_cols}
+                    solved_rules={self.solved_rules}
+                    rules={self.rules}
+                    ordering={self.ordering}
+                    failure={self.failure.clone()}
+                    solving={self.solving}
+                    progress={self.progress.clone()}
+                    shared_step={self.shared_step}
+                    on_tab_change={
+                        ctx.link().callback(|target| Self::Message::TabChanged(target))
+                    }
+                    on_draft_change={
+                        ctx.link().callback(|tiles| Self::Message::DraftChanged(tiles))
+                    }
+                    on_editor_submit={
+                        ctx.link().callback(|tiles| Self::Message::EditorSubmitted(tiles))
+                    }
+                    on_editor_clear={
+                        ctx.link().callback(|_| Self::Message::EditorCleared)
+                    }
+                    on_solver_cancel={
+                        ctx.link().callback(|_| Self::Message::SolverCancelled)
+                    }
+                    on_solution_load={
+                        ctx.link().callback(|solution| Self::Message::SolutionLoaded(solution))
+                    }
+                    on_rows_change={
+                        ctx.link().callback(|rows| Self::Message::RowsChanged(rows))
+                    }
+                    on_cols_change={
+                        ctx.link().callback(|cols| Self::Message::ColsChanged(cols))
+                    }
+                    on_rules_change={
+                        ctx.link().callback(|rules| Self::Message::RulesChanged(rules))
+                    }
+                    on_ordering_change={
+                        ctx.link().callback(|ordering| Self::Message::OrderingChanged(ordering))
+                    }
+                />
+            </div>
+        }
+    }
+}
@@ -0,0 +1,341 @@
+use std::error::Error;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::components::{Coord, CoordDeltaElement, Nodes, Tile, TileMap};
+use super::solver::rules::RuleSet;
+use super::solver::solver::get_board_states;
+
+/*
+ * Coordinates in these documents are in the board as the user sees it,
+ * without the margin the solver adds when paths may go outside. A path may
+ * still pass through that margin, so coordinates are signed.
+ */
+pub type ArchiveCoord = [CoordDeltaElement; 2];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArchiveError {
+    Json(String),
+    OutOfBoard(ArchiveCoord),
+    EmptyPath,
+    MissingTile(ArchiveCoord),
+}
+
+impl fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Json(message) => write!(f, "Invalid JSON: {}", message),
+            Self::OutOfBoard([x, y]) => write!(f, "({}, {}) is outside the board", x, y),
+            Self::EmptyPath => write!(f, "A step has no path"),
+            Self::MissingTile([x, y]) => {
+                write!(f, "A step starts at ({}, {}), which has no tile", x, y)
+            }
+        }
+    }
+}
+
+impl Error for ArchiveError {}
+
+impl From<serde_json::Error> for ArchiveError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err.to_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TileEntry {
+    pub x: usize,
+    pub y: usize,
+    pub tile: Tile,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BoardDocument {
+    pub rows: usize,
+    pub cols: usize,
+    pub tiles: Vec<TileEntry>,
+}
+
+impl BoardDocument {
+    pub fn new(rows: usize, cols: usize, map: &TileMap) -> Self {
+        let mut tiles: Vec<TileEntry> = map
+            .iter()
+            .map(|(&[x, y], &tile)| TileEntry { x, y, tile })
+            .collect();
+        tiles.sort_by_key(|entry| (entry.y, entry.x));
+        Self { rows, cols, tiles }
+    }
+
+    pub fn to_map(&self) -> TileMap {
+        self.tiles
+            .iter()
+            .map(|entry| ([entry.x, entry.y], entry.tile))
+            .collect()
+    }
+
+    fn validate(&self) -> Result<(), ArchiveError> {
+        match self
+            .tiles
+            .iter()
+            .find(|entry| entry.x >= self.cols || entry.y >= self.rows)
+        {
+            Some(entry) => Err(ArchiveError::OutOfBoard([
+                entry.x as CoordDeltaElement,
+                entry.y as CoordDeltaElement,
+            ])),
+            None => Ok(()),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ArchiveError> {
+        let board: Self = serde_json::from_str(json)?;
+        board.validate()?;
+        Ok(board)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SolutionStep {
+    pub tile: Tile,
+    pub endpoints: [ArchiveCoord; 2],
+    pub path: Vec<ArchiveCoord>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SolutionDocument {
+    pub board: BoardDocument,
+    pub rules: RuleSet,
+    pub steps: Vec<SolutionStep>,
+}
+
+impl SolutionDocument {
+    pub fn new(
+        rows: usize,
+        cols: usize,
+        map: &TileMap,
+        rules: &RuleSet,
+        nodes: &[Nodes],
+    ) -> Result<Self, ArchiveError> {
+        /*
+         * `nodes` are in the padded board the solver works on, so `rows`,
+         * `cols` and `rules` must be the ones they were solved with. Tiles may
+         * fall in gravity mode, so the tile of each step is looked up in the
+         * board just before that step.
+         */
+        let padding = rules.padding() as CoordDeltaElement;
+        let unpad = |&[x, y]: &Coord| -> ArchiveCoord {
+            [
+                x as CoordDeltaElement - padding,
+                y as CoordDeltaElement - padding,
+            ]
+        };
+        let states = get_board_states(map, &[cols, rows], nodes, rules);
+        let steps = nodes
+            .iter()
+            .zip(states.iter())
+            .map(|(node, state)| {
+                let (first, last) = match (node.first(), node.last()) {
+                    (Some(first), Some(last)) => (first, last),
+                    _ => return Err(ArchiveError::EmptyPath),
+                };
+                Ok(SolutionStep {
+                    tile: *state
+                        .get(first)
+                        .ok_or_else(|| ArchiveError::MissingTile(unpad(first)))?,
+                    endpoints: [unpad(first), unpad(last)],
+                    path: node.iter().map(unpad).collect(),
+                })
+            })
+            .collect::<Result<Vec<SolutionStep>, ArchiveError>>()?;
+        Ok(Self {
+            board: BoardDocument::new(rows, cols, map),
+            rules: *rules,
+            steps,
+        })
+    }
+
+    pub fn to_nodes(&self) -> Result<Vec<Nodes>, ArchiveError> {
+        let padding = self.rules.padding() as CoordDeltaElement;
+        let [width, height] = [self.board.cols, self.board.rows].map(|v| v as CoordDeltaElement);
+        let pad = |&[x, y]: &ArchiveCoord| -> Result<Coord, ArchiveError> {
+            match [x + padding, y + padding] {
+                [px, py] if px < 0 || py < 0 || x >= width + padding || y >= height + padding => {
+                    Err(ArchiveError::OutOfBoard([x, y]))
+                }
+                [px, py] => Ok([px as usize, py as usize]),
+            }
+        };
+        self.steps
+            .iter()
+            .map(|step| match step.path.is_empty() {
+                true => Err(ArchiveError::EmptyPath),
+                false => step.path.iter().map(pad).collect(),
+            })
+            .collect()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ArchiveError> {
+        let solution: Self = serde_json::from_str(json)?;
+        solution.board.validate()?;
+        solution.to_nodes()?;
+        Ok(solution)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionDocument {
+    // The board in the editor, which may differ from the solved one.
+    pub board: BoardDocument,
+    pub rules: RuleSet,
+    pub solution: Option<SolutionDocument>,
+}
+
+impl SessionDocument {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ArchiveError> {
+        let session: Self = serde_json::from_str(json)?;
+        session.board.validate()?;
+        if let Some(solution) = &session.solution {
+            solution.board.validate()?;
+            solution.to_nodes()?;
+        }
+        Ok(session)
+    }
+}
+
+#[test]
+fn test_board_document() {
+    use super::notation::parse_board;
+
+    let (map, _) = parse_board(
+        "
+        1m .  1m
+        #  2m 2m
+        ",
+    )
+    .unwrap();
+    let board = BoardDocument::new(3, 4, &map);
+    assert_eq!(
+        board.tiles[0],
+        TileEntry {
+            x: 0,
+            y: 0,
+            tile: 0
+        }
+    );
+    assert_eq!(
+        BoardDocument::from_json(&board.to_json()),
+        Ok(board.clone())
+    );
+    assert_eq!(board.to_map(), map);
+
+    assert!(matches!(
+        BoardDocument::from_json("{\"rows\": 1}"),
+        Err(ArchiveError::Json(_))
+    ));
+    let outside = BoardDocument::new(1, 1, &map);
+    assert_eq!(
+        BoardDocument::from_json(&outside.to_json()),
+        Err(ArchiveError::OutOfBoard([2, 0]))
+    );
+}
+
+#[test]
+fn test_solution_document() {
+    use super::notation::parse_board;
+    use super::solver::solver::SichuanSolver;
+
+    let (map, size) = parse_board(
+        "
+        1m 2m 1m
+        2m 3m 3m
+        ",
+    )
+    .unwrap();
+    for rules in [
+        RuleSet::default(),
+        RuleSet {
+            gravity: true,
+            ..RuleSet::default()
+        },
+    ] {
+        let mut solver = SichuanSolver::new(&map, &size, &rules);
+        assert_eq!(solver.solve(), Ok(()));
+        let nodes = solver.result();
+
+        let solution = SolutionDocument::new(2, 3, &map, &rules, &nodes).unwrap();
+        assert_eq!(solution.steps.len(), 3);
+        assert!(solution
+            .steps
+            .iter()
+            .all(|step| step.path.first() == Some(&step.endpoints[0])
+                && step.path.last() == Some(&step.endpoints[1])));
+        assert!(solution.steps.iter().all(|step| step
+            .path
+            .iter()
+            .all(|&[x, y]| (-1..=3).contains(&x) && (-1..=2).contains(&y))));
+
+        let json = solution.to_json();
+        let loaded = SolutionDocument::from_json(&json).unwrap();
+        assert_eq!(loaded, solution);
+        assert_eq!(loaded.to_nodes(), Ok(nodes));
+    }
+
+    /* Nodes solved without the margin are off by one in the padded board. */
+    assert_eq!(
+        SolutionDocument::new(2, 3, &map, &RuleSet::default(), &[vec![[0, 0], [2, 0]]]),
+        Err(ArchiveError::MissingTile([-1, -1]))
+    );
+
+    let mut solution = SolutionDocument::new(2, 3, &map, &RuleSet::default(), &[]).unwrap();
+    solution.steps.push(SolutionStep {
+        tile: 0,
+        endpoints: [[0, 0], [5, 0]],
+        path: vec![[0, 0], [5, 0]],
+    });
+    assert_eq!(
+        SolutionDocument::from_json(&solution.to_json()),
+        Err(ArchiveError::OutOfBoard([5, 0]))
+    );
+}
+
+#[test]
+fn test_session_document() {
+    use super::notation::parse_board;
+
+    let (map, _) = parse_board("1m 1m").unwrap();
+    let rules = RuleSet::default();
+    let solution = SolutionDocument::new(1, 2, &map, &rules, &[vec![[1, 1], [2, 1]]]).unwrap();
+    let session = SessionDocument {
+        board: BoardDocument::new(3, 3, &map),
+        rules,
+        solution: Some(solution.clone()),
+    };
+    assert_eq!(
+        SessionDocument::from_json(&session.to_json()),
+        Ok(session.clone())
+    );
+
+    let mut broken = solution;
+    broken.steps[0].path.clear();
+    let session = SessionDocument {
+        solution: Some(broken),
+        ..session
+    };
+    assert_eq!(
+        SessionDocument::from_json(&session.to_json()),
+        Err(ArchiveError::EmptyPath)
+    );
+}
@@ -0,0 +1,219 @@
+use std::fs;
+use std::io::{self, Read};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use canvas::components::TileMap;
+use canvas::notation::parse_board;
+use canvas::solver::connect::PathSelection;
+use canvas::solver::ordering::MoveOrderingKind;
+use canvas::solver::rules::RuleSet;
+use canvas::solver::solver::{SichuanSolver, SolverError, SolverProgress};
+use canvas::solver::utils::get_node_edges;
+
+const EXIT_SOLVED: u8 = 0;
+const EXIT_UNSOLVABLE: u8 = 1;
+const EXIT_TIMEOUT: u8 = 2;
+const EXIT_INVALID: u8 = 3;
+
+const DEFAULT_MAX_STEPS: usize = 1_000_000;
+const STEPS_PER_CHECK: usize = 1000;
+
+const USAGE: &str = "Usage: sichuan [OPTIONS] <BOARD>
+
+Solves the board in BOARD, or in standard input when BOARD is `-`, and prints
+one removed pair per line.
+
+Each line of the board is a row of cells separated by spaces. A cell is a tile
+code such as `1m`, `5p`, `E` or `Rd`, `.` for an empty cell or `#` for a wall.
+
+Options:
+    --max-turns <N>      Turns a path may take (default: 2)
+    --inside             Paths may not go outside the board
+    --gravity            Tiles fall down after each removal
+    --ordering <NAME>    fewest-remaining, fewest-blocking, shortest-path or
+                         random[:SEED] (default: fewest-remaining)
+    --max-steps <N>      Give up after N search steps (default: 1000000)
+    --timeout <SECONDS>  Give up after SECONDS of search
+    -h, --help           Print this message
+
+Exit codes:
+    0  The board was solved
+    1  The board cannot be solved
+    2  The search ran out of steps or time
+    3  The arguments or the board were invalid";
+
+struct Options {
+    path: String,
+    rules: RuleSet,
+    ordering: MoveOrderingKind,
+    max_steps: usize,
+    timeout: Option<Duration>,
+}
+
+fn parse_value<T: std::str::FromStr>(name: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or(format!("{} needs a value", name))?;
+    value
+        .parse::<T>()
+        .map_err(|_| format!("Invalid value for {}: {}", name, value))
+}
+
+fn parse_timeout(name: &str, value: Option<String>) -> Result<Duration, String> {
+    let seconds: f64 = parse_value(name, value)?;
+    Duration::try_from_secs_f64(seconds)
+        .map_err(|_| format!("Invalid value for {}: {}", name, seconds))
+}
+
+fn parse_ordering(value: &str) -> Result<MoveOrderingKind, String> {
+    match value.split_once(':') {
+        Some(("random", seed)) => seed
+            .parse::<u64>()
+            .map(MoveOrderingKind::Random)
+            .map_err(|_| format!("Invalid seed: {}", seed)),
+        _ => match value {
+            "fewest-remaining" => Ok(MoveOrderingKind::FewestRemaining),
+            "fewest-blocking" => Ok(MoveOrderingKind::FewestBlocking),
+            "shortest-path" => Ok(MoveOrderingKind::ShortestPath),
+            "random" => Ok(MoveOrderingKind::Random(0)),
+            _ => Err(format!("Unknown ordering: {}", value)),
+        },
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut path = None;
+    let mut rules = RuleSet::default();
+    let mut ordering = MoveOrderingKind::default();
+    let mut max_steps = DEFAULT_MAX_STEPS;
+    let mut timeout = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--max-turns" => rules.max_turns = parse_value(&arg, args.next())?,
+            "--inside" => rules.allow_outside = false,
+            "--gravity" => rules.gravity = true,
+            "--ordering" => {
+                ordering = parse_ordering(&args.next().ok_or("--ordering needs a value")?)?
+            }
+            "--max-steps" => max_steps = parse_value(&arg, args.next())?,
+            "--timeout" => timeout = Some(parse_timeout(&arg, args.next())?),
+            _ if arg.starts_with("--") => return Err(format!("Unknown option: {}", arg)),
+            _ if path.is_none() => path = Some(arg),
+            _ => return Err(format!("Unexpected argument: {}", arg)),
+        }
+    }
+
+    match path {
+        Some(path) => Ok(Some(Options {
+            path,
+            rules,
+            ordering,
+            max_steps,
+            timeout,
+        })),
+        None => Err(String::from("No board was given")),
+    }
+}
+
+fn read_board(path: &str) -> Result<(TileMap, [usize; 2]), String> {
+    let (name, text) = match path {
+        "-" => {
+            let mut text = String::new();
+            let result = io::stdin().read_to_string(&mut text).map(|_| text);
+            ("standard input", result)
+        }
+        _ => (path, fs::read_to_string(path)),
+    };
+    let text = text.map_err(|err| format!("Failed to read {}: {}", name, err))?;
+    parse_board(&text).map_err(|err| format!("Invalid board in {}: {}", name, err))
+}
+
+fn solve(options: &Options, map: &TileMap, size: &[usize; 2]) -> u8 {
+    let mut solver =
+        SichuanSolver::with_ordering(map, size, &options.rules, options.ordering.create());
+    solver.set_max_steps(Some(options.max_steps));
+    solver.set_path_selection(PathSelection::Shortest);
+
+    let started_at = Instant::now();
+    let result = loop {
+        match solver.solve_for(STEPS_PER_CHECK) {
+            SolverProgress::Pending => {
+                if matches!(options.timeout, Some(timeout) if started_at.elapsed() >= timeout) {
+                    eprintln!("The search was aborted after {:?}", started_at.elapsed());
+                    return EXIT_TIMEOUT;
+                }
+            }
+            SolverProgress::Solved => break Ok(()),
+            SolverProgress::Failed(err) => break Err(err),
+        }
+    };
+
+    match result {
+        Ok(()) => {
+            let padding = options.rules.padding();
+            for node in solver.result() {
+                let [[x1, y1], [x2, y2]] = get_node_edges(&node);
+                println!(
+                    "{},{} {},{}",
+                    x1 - padding,
+                    y1 - padding,
+                    x2 - padding,
+                    y2 - padding
+                );
+            }
+            EXIT_SOLVED
+        }
+        Err(err) => {
+            eprintln!("{}", err);
+            match err {
+                SolverError::BudgetExceeded(_) => EXIT_TIMEOUT,
+                _ => EXIT_UNSOLVABLE,
+            }
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::from(EXIT_SOLVED);
+        }
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, USAGE);
+            return ExitCode::from(EXIT_INVALID);
+        }
+    };
+
+    match read_board(&options.path) {
+        Ok((map, size)) => ExitCode::from(solve(&options, &map, &size)),
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::from(EXIT_INVALID)
+        }
+    }
+}
+
+#[test]
+fn test_parse_args() {
+    let args = |line: &str| parse_args(line.split_whitespace().map(String::from));
+
+    let options = args("--inside --max-turns 3 --ordering random:7 board.txt")
+        .unwrap()
+        .unwrap();
+    assert_eq!(options.path, "board.txt");
+    assert!(!options.rules.allow_outside);
+    assert_eq!(options.rules.max_turns, 3);
+    assert_eq!(options.ordering, MoveOrderingKind::Random(7));
+    assert_eq!(options.max_steps, DEFAULT_MAX_STEPS);
+
+    assert!(args("--help").unwrap().is_none());
+    assert!(args("").is_err());
+    assert!(args("--max-steps many board.txt").is_err());
+
+    let options = args("--timeout 1.5 board.txt").unwrap().unwrap();
+    assert_eq!(options.timeout, Some(Duration::from_millis(1500)));
+    assert!(args("--timeout -1 board.txt").is_err());
+    assert!(args("--timeout NaN board.txt").is_err());
+}
@@ -0,0 +1,14 @@
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    use gloo_worker::Registrable;
+
+    use canvas::worker::SolverWorker;
+
+    SolverWorker::registrar().register();
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    eprintln!("The solver worker only builds for wasm32.");
+    std::process::exit(1);
+}
@@ -0,0 +1,142 @@
+use super::components::Tile;
+use super::solver::classes::TileClasses;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Suit {
+    Characters,
+    Bamboos,
+    Dots,
+    Winds,
+    Dragons,
+    Flowers,
+    Seasons,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TileInfo {
+    pub suit: Suit,
+    pub rank: u8,
+    // Short code used by the text board format, such as "1m" or "Rd".
+    pub code: &'static str,
+    pub name: &'static str,
+}
+
+const fn info(suit: Suit, rank: u8, code: &'static str, name: &'static str) -> TileInfo {
+    TileInfo {
+        suit,
+        rank,
+        code,
+        name,
+    }
+}
+
+pub const SUITS: [Suit; 7] = [
+    Suit::Characters,
+    Suit::Bamboos,
+    Suit::Dots,
+    Suit::Winds,
+    Suit::Dragons,
+    Suit::Flowers,
+    Suit::Seasons,
+];
+
+pub const N_TILES: usize = 42;
+pub const TILE_CATALOGUE: [TileInfo; N_TILES] = [
+    info(Suit::Characters, 1, "1m", "One of Characters"),
+    info(Suit::Characters, 2, "2m", "Two of Characters"),
+    info(Suit::Characters, 3, "3m", "Three of Characters"),
+    info(Suit::Characters, 4, "4m", "Four of Characters"),
+    info(Suit::Characters, 5, "5m", "Five of Characters"),
+    info(Suit::Characters, 6, "6m", "Six of Characters"),
+    info(Suit::Characters, 7, "7m", "Seven of Characters"),
+    info(Suit::Characters, 8, "8m", "Eight of Characters"),
+    info(Suit::Characters, 9, "9m", "Nine of Characters"),
+    info(Suit::Bamboos, 1, "1s", "One of Bamboos"),
+    info(Suit::Bamboos, 2, "2s", "Two of Bamboos"),
+    info(Suit::Bamboos, 3, "3s", "Three of Bamboos"),
+    info(Suit::Bamboos, 4, "4s", "Four of Bamboos"),
+    info(Suit::Bamboos, 5, "5s", "Five of Bamboos"),
+    info(Suit::Bamboos, 6, "6s", "Six of Bamboos"),
+    info(Suit::Bamboos, 7, "7s", "Seven of Bamboos"),
+    info(Suit::Bamboos, 8, "8s", "Eight of Bamboos"),
+    info(Suit::Bamboos, 9, "9s", "Nine of Bamboos"),
+    info(Suit::Dots, 1, "1p", "One of Dots"),
+    info(Suit::Dots, 2, "2p", "Two of Dots"),
+    info(Suit::Dots, 3, "3p", "Three of Dots"),
+    info(Suit::Dots, 4, "4p", "Four of Dots"),
+    info(Suit::Dots, 5, "5p", "Five of Dots"),
+    info(Suit::Dots, 6, "6p", "Six of Dots"),
+    info(Suit::Dots, 7, "7p", "Seven of Dots"),
+    info(Suit::Dots, 8, "8p", "Eight of Dots"),
+    info(Suit::Dots, 9, "9p", "Nine of Dots"),
+    info(Suit::Winds, 1, "E", "East Wind"),
+    info(Suit::Winds, 2, "S", "South Wind"),
+    info(Suit::Winds, 3, "W", "West Wind"),
+    info(Suit::Winds, 4, "N", "North Wind"),
+    info(Suit::Dragons, 1, "Rd", "Red Dragon"),
+    info(Suit::Dragons, 2, "Gd", "Green Dragon"),
+    info(Suit::Dragons, 3, "Wd", "White Dragon"),
+    info(Suit::Flowers, 1, "Pl", "Plum"),
+    info(Suit::Flowers, 2, "Or", "Orchid"),
+    info(Suit::Flowers, 3, "Ch", "Chrysanthemum"),
+    info(Suit::Flowers, 4, "Bm", "Bamboo"),
+    info(Suit::Seasons, 1, "Sp", "Spring"),
+    info(Suit::Seasons, 2, "Su", "Summer"),
+    info(Suit::Seasons, 3, "Au", "Autumn"),
+    info(Suit::Seasons, 4, "Wi", "Winter"),
+];
+
+pub const FLOWERS: [Tile; 4] = [34, 35, 36, 37];
+pub const SEASONS: [Tile; 4] = [38, 39, 40, 41];
+
+pub fn create_bonus_tile_classes() -> TileClasses {
+    /*
+     * Any flower matches any other flower, and likewise for seasons.
+     */
+    TileClasses::new(&[&FLOWERS, &SEASONS])
+}
+
+pub fn get_tile_info(tile: Tile) -> Option<&'static TileInfo> {
+    TILE_CATALOGUE.get(tile as usize)
+}
+
+pub fn get_tile_by_code(code: &str) -> Option<Tile> {
+    TILE_CATALOGUE
+        .iter()
+        .position(|info| info.code == code)
+        .map(|i| i as Tile)
+}
+
+pub fn get_tiles_of_suit(suit: Suit) -> Vec<Tile> {
+    (0..N_TILES as Tile)
+        .filter(|&tile| TILE_CATALOGUE[tile as usize].suit == suit)
+        .collect()
+}
+
+#[test]
+fn test_tile_catalogue() {
+    use super::components::WALL;
+    use std::collections::HashSet;
+
+    assert_eq!(get_tiles_of_suit(Suit::Flowers), FLOWERS.to_vec());
+    assert_eq!(get_tiles_of_suit(Suit::Seasons), SEASONS.to_vec());
+    assert_eq!(get_tiles_of_suit(Suit::Dots).len(), 9);
+    assert_eq!(get_tile_info(27).map(|info| info.name), Some("East Wind"));
+    assert_eq!(get_tile_info(WALL), None);
+
+    let keys: HashSet<(Suit, u8)> = TILE_CATALOGUE
+        .iter()
+        .map(|info| (info.suit, info.rank))
+        .collect();
+    assert_eq!(keys.len(), N_TILES);
+
+    let codes: HashSet<&str> = TILE_CATALOGUE.iter().map(|info| info.code).collect();
+    assert_eq!(codes.len(), N_TILES);
+    assert_eq!(get_tile_by_code("Rd"), Some(31));
+    assert_eq!(get_tile_by_code("5p"), Some(22));
+    assert_eq!(get_tile_by_code("0m"), None);
+
+    let classes = create_bonus_tile_classes();
+    assert!(classes.is_matching(FLOWERS[0], FLOWERS[3]));
+    assert!(!classes.is_matching(FLOWERS[0], SEASONS[0]));
+}
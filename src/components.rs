@@ -8,3 +8,6 @@ pub type CoordDelta = [CoordDeltaElement; 2];
 pub type Grid = [Coord; 2];
 pub type Nodes = Vec<Coord>;
 pub type TileMap = HashMap<Coord, Tile>;
+
+// A blocked cell which holds no tile and cannot be passed through.
+pub const WALL: Tile = Tile::MAX;
@@ -0,0 +1,9 @@
+pub mod archive;
+pub mod catalogue;
+pub mod components;
+pub mod notation;
+pub mod recognition;
+pub mod share;
+pub mod solver;
+#[cfg(target_arch = "wasm32")]
+pub mod worker;
@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use super::catalogue::{get_tile_by_code, get_tile_info};
+use super::components::{TileMap, WALL};
+#[cfg(test)]
+use super::solver::utils::get_size_from_map;
+
+/*
+ * Text board format: one row per line, cells separated by spaces. A cell is
+ * a tile code from the catalogue such as `1m`, `5p`, `E` or `Rd`, `.` for an
+ * empty cell or `#` for a wall. Blank lines are skipped, and every row must
+ * have the same number of cells. Rows and columns of empty cells count
+ * towards the size of the board.
+ */
+const EMPTY_CODE: &str = ".";
+const WALL_CODE: &str = "#";
+const CELL_WIDTH: usize = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    UnknownTile(String),
+    RowLength { expected: usize, found: usize },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    // Both are counted from 1.
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Line {}, column {}: ", self.line, self.column)?;
+        match &self.kind {
+            ParseErrorKind::UnknownTile(token) => write!(f, "unknown tile code `{}`", token),
+            ParseErrorKind::RowLength { expected, found } => {
+                write!(f, "expected {} cells but found {}", expected, found)
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+fn split_cells(line: &str) -> Vec<(usize, &str)> {
+    /*
+     * Each cell comes with the column it starts at, counted in characters.
+     */
+    let mut cells = Vec::new();
+    let mut start: Option<(usize, usize)> = None;
+    for (i, (offset, c)) in line.char_indices().chain([(line.len(), ' ')]).enumerate() {
+        match (c.is_whitespace(), start) {
+            (false, None) => start = Some((i + 1, offset)),
+            (true, Some((column, begin))) => {
+                cells.push((column, &line[begin..offset]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    cells
+}
+
+pub fn parse_board(text: &str) -> Result<(TileMap, [usize; 2]), ParseError> {
+    /*
+     * The size is returned as `[cols, rows]` along with the map, since empty
+     * rows and columns at the end are not in the map.
+     */
+    let mut map: TileMap = HashMap::new();
+    let mut width: Option<usize> = None;
+    let mut height = 0;
+    let rows = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    for (y, (i, line)) in rows.enumerate() {
+        height = y + 1;
+        let cells = split_cells(line);
+        let expected = *width.get_or_insert(cells.len());
+        if cells.len() != expected {
+            let column = match cells.get(expected) {
+                Some(&(column, _)) => column,
+                None => line.chars().count() + 1,
+            };
+            return Err(ParseError {
+                line: i + 1,
+                column,
+                kind: ParseErrorKind::RowLength {
+                    expected,
+                    found: cells.len(),
+                },
+            });
+        }
+
+        for (x, &(column, token)) in cells.iter().enumerate() {
+            let tile = match token {
+                EMPTY_CODE => continue,
+                WALL_CODE => WALL,
+                _ => get_tile_by_code(token).ok_or_else(|| ParseError {
+                    line: i + 1,
+                    column,
+                    kind: ParseErrorKind::UnknownTile(token.to_string()),
+                })?,
+            };
+            map.insert([x, y], tile);
+        }
+    }
+    Ok((map, [width.unwrap_or(0), height]))
+}
+
+pub fn format_board(map: &TileMap, size: &[usize; 2]) -> Option<String> {
+    /*
+     * Cells are padded to a common width so that columns line up. `None` is
+     * returned if a tile lies outside `size` or has no code in the catalogue.
+     */
+    let [width, height] = *size;
+    if map.keys().any(|&[x, y]| x >= width || y >= height) {
+        return None;
+    }
+
+    let mut text = String::new();
+    for y in 0..height {
+        let mut row: Vec<String> = Vec::new();
+        for x in 0..width {
+            let code = match map.get(&[x, y]) {
+                None => EMPTY_CODE,
+                Some(&WALL) => WALL_CODE,
+                Some(&tile) => get_tile_info(tile)?.code,
+            };
+            row.push(format!("{:<width$}", code, width = CELL_WIDTH));
+        }
+        text.push_str(row.join(" ").trim_end());
+        text.push('\n');
+    }
+    Some(text)
+}
+
+#[test]
+fn test_parse_board() {
+    let (map, size) = parse_board(
+        "
+        1m .  1m 5p
+        E  #  Rd 5p
+
+        E  .  .  Rd
+        ",
+    )
+    .unwrap();
+    assert_eq!(
+        map,
+        HashMap::from([
+            ([0, 0], 0),
+            ([2, 0], 0),
+            ([3, 0], 22),
+            ([0, 1], 27),
+            ([1, 1], WALL),
+            ([2, 1], 31),
+            ([3, 1], 22),
+            ([0, 2], 27),
+            ([3, 2], 31),
+        ])
+    );
+    assert_eq!(size, [4, 3]);
+    assert_eq!(get_size_from_map(&map), size);
+    assert_eq!(parse_board(""), Ok((HashMap::new(), [0, 0])));
+
+    let (map, size) = parse_board("1m .  .\n.  .  .\n").unwrap();
+    assert_eq!(map, HashMap::from([([0, 0], 0)]));
+    assert_eq!(size, [3, 2]);
+}
+
+#[test]
+fn test_parse_board_errors() {
+    assert_eq!(
+        parse_board("1m 1m\n1m 0m"),
+        Err(ParseError {
+            line: 2,
+            column: 4,
+            kind: ParseErrorKind::UnknownTile(String::from("0m")),
+        })
+    );
+    assert_eq!(
+        parse_board("\n1m 2m 3m\n1m 2m"),
+        Err(ParseError {
+            line: 3,
+            column: 6,
+            kind: ParseErrorKind::RowLength {
+                expected: 3,
+                found: 2,
+            },
+        })
+    );
+    assert_eq!(
+        parse_board("1m 2m\n1m 2m 3m").map_err(|err| err.column),
+        Err(7)
+    );
+    assert_eq!(
+        parse_board("1m x").map_err(|err| err.to_string()),
+        Err(String::from("Line 1, column 4: unknown tile code `x`"))
+    );
+}
+
+#[test]
+fn test_format_board() {
+    let text = "1m .  1m 5p\nE  #  Rd 5p\nE  .  .  Rd\n";
+    let (map, size) = parse_board(text).unwrap();
+    assert_eq!(format_board(&map, &size), Some(String::from(text)));
+
+    let text = "1m .  .\n.  .  .\n";
+    let (map, size) = parse_board(text).unwrap();
+    assert_eq!(format_board(&map, &size), Some(String::from(text)));
+
+    assert_eq!(format_board(&HashMap::new(), &[0, 0]), Some(String::new()));
+    assert_eq!(format_board(&HashMap::from([([0, 0], 100)]), &[1, 1]), None);
+    assert_eq!(format_board(&map, &[0, 0]), None);
+}
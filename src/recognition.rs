@@ -0,0 +1,500 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use super::components::{Coord, Tile, TileMap};
+
+/*
+ * Board recognition from a screenshot. The grid is found from the outline of
+ * the tiles against the table, then every cell is compared with the tile
+ * templates by normalized cross-correlation. Cells whose best match is weak,
+ * or barely better than the runner-up, are reported for manual review.
+ */
+const BACKGROUND_THRESHOLD: f32 = 0.12;
+const MIN_CELL_SIZE: usize = 8;
+const MIN_OCCUPIED_PIXELS: usize = MIN_CELL_SIZE / 2;
+const EDGE_TOLERANCE: f64 = 0.1;
+const CELL_SCORE_RATIO: f32 = 0.9;
+const MIN_TILE_ASPECT: f64 = 0.6;
+const MAX_TILE_ASPECT: f64 = 1.0;
+const CELL_INSET: f64 = 0.08;
+const MIN_FILLED_RATIO: f32 = 0.5;
+const MIN_CONFIDENCE: f32 = 0.6;
+const MIN_MARGIN: f32 = 0.05;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrayImage {
+    pub width: usize,
+    pub height: usize,
+    // Row by row, from 0 for black to 1 for white.
+    pub pixels: Vec<f32>,
+}
+
+impl GrayImage {
+    pub fn new(width: usize, height: usize, pixels: Vec<f32>) -> Option<Self> {
+        match pixels.len() == width * height {
+            true => Some(Self {
+                width,
+                height,
+                pixels,
+            }),
+            false => None,
+        }
+    }
+
+    pub fn from_rgba(width: usize, height: usize, rgba: &[u8]) -> Option<Self> {
+        /*
+         * Transparent pixels are composited over white, the color tile faces
+         * are drawn on.
+         */
+        let pixels = rgba
+            .chunks_exact(4)
+            .map(|pixel| {
+                let [r, g, b, a] =
+                    [pixel[0], pixel[1], pixel[2], pixel[3]].map(|v| v as f32 / 255.0);
+                let luma = 0.299 * r + 0.587 * g + 0.114 * b;
+                luma * a + (1.0 - a)
+            })
+            .collect();
+        Self::new(width, height, pixels)
+    }
+
+    fn get(&self, x: usize, y: usize) -> f32 {
+        self.pixels[y * self.width + x]
+    }
+
+    fn sample(&self, area: [f64; 4], out_width: usize, out_height: usize) -> Vec<f32> {
+        /*
+         * Each output pixel is the mean of the source pixels it covers, so the
+         * result barely depends on the scale of the source.
+         */
+        let [left, top, width, height] = area;
+        let span = |start: f64, size: f64, i: usize, n: usize, limit: usize| {
+            let begin = (start + size * i as f64 / n as f64).max(0.0).floor() as usize;
+            let end = (start + size * (i + 1) as f64 / n as f64).ceil() as usize;
+            let begin = begin.min(limit - 1);
+            begin..end.clamp(begin + 1, limit)
+        };
+
+        let mut pixels = Vec::with_capacity(out_width * out_height);
+        for oy in 0..out_height {
+            let ys = span(top, height, oy, out_height, self.height);
+            for ox in 0..out_width {
+                let xs = span(left, width, ox, out_width, self.width);
+                let n = (xs.len() * ys.len()) as f32;
+                let sum: f32 = ys
+                    .clone()
+                    .flat_map(|y| xs.clone().map(move |x| (x, y)))
+                    .map(|(x, y)| self.get(x, y))
+                    .sum();
+                pixels.push(sum / n);
+            }
+        }
+        pixels
+    }
+
+    fn background_level(&self) -> f32 {
+        /*
+         * The table is assumed to show around the board, so the median of
+         * the image border is taken as its color.
+         */
+        let (w, h) = (self.width, self.height);
+        let mut border: Vec<f32> = (0..w)
+            .flat_map(|x| [self.get(x, 0), self.get(x, h - 1)])
+            .chain((0..h).flat_map(|y| [self.get(0, y), self.get(w - 1, y)]))
+            .collect();
+        border.sort_by(|a, b| a.total_cmp(b));
+        border[border.len() / 2]
+    }
+
+    fn foreground(&self) -> Self {
+        let background = self.background_level();
+        let pixels = self
+            .pixels
+            .iter()
+            .map(|&v| match (v - background).abs() > BACKGROUND_THRESHOLD {
+                true => 1.0,
+                false => 0.0,
+            })
+            .collect();
+        Self { pixels, ..*self }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Grid {
+    pub left: f64,
+    pub top: f64,
+    pub cell_width: f64,
+    pub cell_height: f64,
+    pub rows: usize,
+    pub cols: usize,
+}
+
+impl Grid {
+    fn cell_area(&self, [x, y]: Coord, inset: f64) -> [f64; 4] {
+        let [dx, dy] = [self.cell_width * inset, self.cell_height * inset];
+        [
+            self.left + self.cell_width * x as f64 + dx,
+            self.top + self.cell_height * y as f64 + dy,
+            self.cell_width - dx * 2.0,
+            self.cell_height - dy * 2.0,
+        ]
+    }
+}
+
+fn get_occupied_range(counts: &[usize]) -> Option<(usize, usize)> {
+    let first = counts.iter().position(|&n| n >= MIN_OCCUPIED_PIXELS)?;
+    let last = counts.iter().rposition(|&n| n >= MIN_OCCUPIED_PIXELS)?;
+    Some((first, last))
+}
+
+fn rank_cell_counts(profile: &[f32], extent: usize) -> Vec<usize> {
+    /*
+     * Tile edges line up across the board, so the right number of cells puts
+     * every boundary on a strong edge of the profile and every cell center
+     * away from one. An odd divisor of that count does nearly as well, so
+     * the counts that score close to the best come first, most cells first,
+     * followed by the others from the best score down.
+     */
+    let edge = |position: f64, tolerance: usize| {
+        let center = position.round() as usize;
+        let begin = center.saturating_sub(tolerance).min(profile.len() - 1);
+        let end = (center + tolerance + 1).min(profile.len());
+        profile[begin..end].iter().cloned().fold(0.0, f32::max)
+    };
+    let score = |cells: usize| {
+        let size = extent as f64 / cells as f64;
+        let tolerance = (size * EDGE_TOLERANCE) as usize;
+        let boundaries: f32 = (0..=cells).map(|i| edge(size * i as f64, tolerance)).sum();
+        let centers: f32 = (0..cells)
+            .map(|i| edge(size * (i as f64 + 0.5), tolerance))
+            .sum();
+        boundaries / (cells + 1) as f32 - centers / cells as f32
+    };
+    let mut scores: Vec<(usize, f32)> = (1..=(extent / MIN_CELL_SIZE).max(1))
+        .map(|cells| (cells, score(cells)))
+        .collect();
+    let best = scores.iter().map(|&(_, v)| v).fold(f32::MIN, f32::max);
+    let close = |v: f32| best > 0.0 && v >= best * CELL_SCORE_RATIO;
+    scores.sort_by(|a, b| match (close(a.1), close(b.1)) {
+        (true, true) => b.0.cmp(&a.0),
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        (false, false) => b.1.total_cmp(&a.1),
+    });
+    scores.into_iter().map(|(cells, _)| cells).collect()
+}
+
+pub fn find_grid(image: &GrayImage) -> Option<Grid> {
+    if image.width == 0 || image.height == 0 {
+        return None;
+    }
+
+    let foreground = image.foreground();
+    let mut col_counts = vec![0; image.width];
+    let mut row_counts = vec![0; image.height];
+    for (i, &v) in foreground.pixels.iter().enumerate() {
+        if v > 0.0 {
+            col_counts[i % image.width] += 1;
+            row_counts[i / image.width] += 1;
+        }
+    }
+    let (left, right) = get_occupied_range(&col_counts)?;
+    let (top, bottom) = get_occupied_range(&row_counts)?;
+
+    /*
+     * The profiles start one pixel early so that the first edge, from the
+     * table into the tiles, is part of them.
+     */
+    let (x0, y0) = (left.saturating_sub(1), top.saturating_sub(1));
+    let mut col_profile = vec![0.0; right + 1 - x0];
+    let mut row_profile = vec![0.0; bottom + 1 - y0];
+    for y in top..=bottom {
+        for x in x0..=right {
+            if x + 1 < image.width {
+                col_profile[x - x0] += (image.get(x + 1, y) - image.get(x, y)).abs();
+            }
+        }
+    }
+    for x in left..=right {
+        for y in y0..=bottom {
+            if y + 1 < image.height {
+                row_profile[y - y0] += (image.get(x, y + 1) - image.get(x, y)).abs();
+            }
+        }
+    }
+
+    /*
+     * Counts that make implausibly shaped tiles are passed over, which
+     * matters when the board is only a cell or two across.
+     */
+    let [width, height] = [right + 1 - left, bottom + 1 - top].map(|v| v as f64);
+    let col_ranks = rank_cell_counts(&col_profile, right + 1 - left);
+    let row_ranks = rank_cell_counts(&row_profile, bottom + 1 - top);
+    let mut candidates: Vec<(usize, usize, usize)> = col_ranks
+        .iter()
+        .enumerate()
+        .flat_map(|(i, &cols)| {
+            row_ranks
+                .iter()
+                .enumerate()
+                .map(move |(j, &rows)| (i + j, cols, rows))
+        })
+        .collect();
+    candidates.sort_by_key(|&(rank, _, _)| rank);
+    let (_, cols, rows) = candidates
+        .iter()
+        .find(|&&(_, cols, rows)| {
+            let aspect = (width / cols as f64) / (height / rows as f64);
+            (MIN_TILE_ASPECT..=MAX_TILE_ASPECT).contains(&aspect)
+        })
+        .unwrap_or(&candidates[0]);
+    Some(Grid {
+        left: left as f64,
+        top: top as f64,
+        cell_width: width / *cols as f64,
+        cell_height: height / *rows as f64,
+        rows: *rows,
+        cols: *cols,
+    })
+}
+
+fn normalize(pixels: Vec<f32>) -> Option<Vec<f32>> {
+    /*
+     * Zero mean and unit length, so that the dot product of two cells is
+     * their correlation regardless of brightness and contrast.
+     */
+    let mean = pixels.iter().sum::<f32>() / pixels.len() as f32;
+    let centered: Vec<f32> = pixels.iter().map(|v| v - mean).collect();
+    let norm = centered.iter().map(|v| v * v).sum::<f32>().sqrt();
+    match norm > 1e-3 {
+        true => Some(centered.iter().map(|v| v / norm).collect()),
+        false => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateSet {
+    width: usize,
+    height: usize,
+    templates: Vec<(Tile, Vec<f32>)>,
+}
+
+impl TemplateSet {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            templates: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, tile: Tile, image: &GrayImage) {
+        /*
+         * The image is the whole tile. Its border is cut off the same way as
+         * for the cells of a screenshot.
+         */
+        let grid = Grid {
+            left: 0.0,
+            top: 0.0,
+            cell_width: image.width as f64,
+            cell_height: image.height as f64,
+            rows: 1,
+            cols: 1,
+        };
+        let pixels = image.sample(grid.cell_area([0, 0], CELL_INSET), self.width, self.height);
+        if let Some(template) = normalize(pixels) {
+            self.templates.push((tile, template));
+        }
+    }
+
+    fn classify(&self, cell: &[f32]) -> Option<(Tile, f32, f32)> {
+        /*
+         * Returns the best tile with its score, and the score of the best
+         * other tile.
+         */
+        let mut scores: Vec<(Tile, f32)> = self
+            .templates
+            .iter()
+            .map(|(tile, template)| (*tile, template.iter().zip(cell).map(|(a, b)| a * b).sum()))
+            .collect();
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let &(tile, best) = scores.first()?;
+        let second = scores
+            .iter()
+            .find(|&&(other, _)| other != tile)
+            .map_or(-1.0, |&(_, score)| score);
+        Some((tile, best, second))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recognition {
+    pub grid: Grid,
+    pub tiles: TileMap,
+    // Cells to check by hand. A cell without a match has no tile.
+    pub uncertain: Vec<Coord>,
+}
+
+pub fn recognize_board(image: &GrayImage, templates: &TemplateSet) -> Option<Recognition> {
+    let grid = find_grid(image)?;
+    let foreground = image.foreground();
+    let mut tiles: TileMap = HashMap::new();
+    let mut uncertain: Vec<Coord> = Vec::new();
+    for y in 0..grid.rows {
+        for x in 0..grid.cols {
+            let filled = foreground.sample(grid.cell_area([x, y], 0.0), 1, 1)[0];
+            if filled < MIN_FILLED_RATIO {
+                continue;
+            }
+
+            let cell = image.sample(
+                grid.cell_area([x, y], CELL_INSET),
+                templates.width,
+                templates.height,
+            );
+            match normalize(cell).and_then(|cell| templates.classify(&cell)) {
+                Some((tile, best, second)) => {
+                    tiles.insert([x, y], tile);
+                    if best < MIN_CONFIDENCE || best - second < MIN_MARGIN {
+                        uncertain.push([x, y]);
+                    }
+                }
+                None => uncertain.push([x, y]),
+            }
+        }
+    }
+    Some(Recognition {
+        grid,
+        tiles,
+        uncertain,
+    })
+}
+
+#[cfg(test)]
+fn create_pattern(seed: u64, width: usize, height: usize) -> GrayImage {
+    use super::solver::utils::SeededRng;
+
+    /*
+     * A 4 by 4 grid of blocks, cut at random places so that the edges of
+     * different patterns do not line up.
+     */
+    let mut rng = SeededRng::new(seed);
+    let blocks: Vec<f32> = (0..16).map(|_| 0.4 + rng.below(7) as f32 * 0.1).collect();
+    let mut cuts = |size: usize| -> Vec<usize> {
+        let mut cuts: Vec<usize> = (0..3).map(|_| (10 + rng.below(80)) * size / 100).collect();
+        cuts.sort_unstable();
+        cuts
+    };
+    let (x_cuts, y_cuts) = (cuts(width), cuts(height));
+    let block = |cuts: &[usize], v: usize| cuts.iter().filter(|&&cut| cut <= v).count();
+    let pixels = (0..width * height)
+        .map(|i| blocks[block(&y_cuts, i / width) * 4 + block(&x_cuts, i % width)])
+        .collect();
+    GrayImage::new(width, height, pixels).unwrap()
+}
+
+#[cfg(test)]
+fn create_screenshot(cells: &HashMap<Coord, GrayImage>, scale: usize) -> GrayImage {
+    /*
+     * Tiles are scaled up with a dark outline, on a table with a margin of
+     * 10 pixels.
+     */
+    let (margin, table, outline) = (10, 0.2, 0.05);
+    let [tile_width, tile_height] = [24 * scale, 30 * scale];
+    let cols = cells.keys().map(|&[x, _]| x + 1).max().unwrap();
+    let rows = cells.keys().map(|&[_, y]| y + 1).max().unwrap();
+    let (width, height) = (
+        cols * tile_width + margin * 2,
+        rows * tile_height + margin * 2,
+    );
+    let mut pixels = vec![table; width * height];
+    for (&[cx, cy], pattern) in cells {
+        for ty in 0..tile_height {
+            for tx in 0..tile_width {
+                let border = tx < scale
+                    || ty < scale
+                    || tx >= tile_width - scale
+                    || ty >= tile_height - scale;
+                let value = match border {
+                    true => outline,
+                    false => pattern.get(tx / scale, ty / scale),
+                };
+                let (x, y) = (
+                    margin + cx * tile_width + tx,
+                    margin + cy * tile_height + ty,
+                );
+                pixels[y * width + x] = value;
+            }
+        }
+    }
+    GrayImage::new(width, height, pixels).unwrap()
+}
+
+#[test]
+fn test_gray_image() {
+    let rgba = [255, 255, 255, 255, 0, 0, 0, 255, 0, 0, 0, 0];
+    assert_eq!(
+        GrayImage::from_rgba(3, 1, &rgba).map(|image| image.pixels),
+        Some(vec![1.0, 0.0, 1.0])
+    );
+    assert_eq!(GrayImage::from_rgba(2, 1, &rgba), None);
+
+    let image = GrayImage::new(4, 2, vec![0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 1.0]).unwrap();
+    assert_eq!(image.sample([0.0, 0.0, 4.0, 2.0], 2, 1), vec![0.0, 1.0]);
+    assert_eq!(image.sample([1.0, 0.0, 2.0, 2.0], 1, 1), vec![0.5]);
+}
+
+#[test]
+fn test_find_grid() {
+    assert_eq!(find_grid(&GrayImage::new(0, 0, Vec::new()).unwrap()), None);
+    assert_eq!(
+        find_grid(&GrayImage::new(20, 20, vec![0.2; 400]).unwrap()),
+        None
+    );
+
+    for (scale, [cols, rows]) in [(1, [3, 2]), (2, [4, 3]), (3, [5, 2]), (2, [1, 3])] {
+        let cells: HashMap<Coord, GrayImage> = (0..cols * rows)
+            .map(|i| ([i % cols, i / cols], create_pattern(i as u64, 24, 30)))
+            .collect();
+        let grid = find_grid(&create_screenshot(&cells, scale)).unwrap();
+        assert_eq!([grid.cols, grid.rows], [cols, rows]);
+        assert_eq!([grid.left, grid.top], [10.0, 10.0]);
+        assert!((grid.cell_width - (24 * scale) as f64).abs() < 1.0);
+        assert!((grid.cell_height - (30 * scale) as f64).abs() < 1.0);
+    }
+}
+
+#[test]
+fn test_recognize_board() {
+    let mut templates = TemplateSet::new(24, 30);
+    for tile in 0..6 {
+        templates.add(tile, &create_pattern(tile as u64, 48, 60));
+    }
+
+    let layout: HashMap<Coord, Tile> = HashMap::from([
+        ([0, 0], 0),
+        ([1, 0], 1),
+        ([2, 0], 2),
+        ([3, 0], 0),
+        ([0, 1], 3),
+        ([3, 1], 4),
+        ([0, 2], 5),
+        ([1, 2], 5),
+        ([2, 2], 1),
+        ([3, 2], 2),
+    ]);
+    let mut cells: HashMap<Coord, GrayImage> = layout
+        .iter()
+        .map(|(&coord, &tile)| (coord, create_pattern(tile as u64, 24, 30)))
+        .collect();
+    let recognition = recognize_board(&create_screenshot(&cells, 2), &templates).unwrap();
+    assert_eq!([recognition.grid.cols, recognition.grid.rows], [4, 3]);
+    assert_eq!(recognition.tiles, layout);
+    assert!(recognition.uncertain.is_empty());
+
+    cells.insert([1, 1], create_pattern(100, 24, 30));
+    let recognition = recognize_board(&create_screenshot(&cells, 2), &templates).unwrap();
+    assert!(recognition.tiles.contains_key(&[1, 1]));
+    assert_eq!(recognition.uncertain, vec![[1, 1]]);
+}
@@ -0,0 +1,380 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use super::catalogue::create_bonus_tile_classes;
+use super::components::{Coord, Nodes, Tile, TileMap};
+use super::solver::classes::TileClasses;
+use super::solver::connect::PathSelection;
+use super::solver::rules::RuleSet;
+use super::solver::solver::route_moves;
+
+/*
+ * Packed layout, encoded in URL-safe base64 without padding:
+ *
+ * byte 0     layout version
+ * bytes 1-2  rows (big endian)
+ * bytes 3-4  cols (big endian)
+ * byte 5     max turns
+ * byte 6     rule flags
+ * bytes 7-   one byte per cell, row by row: the tile, or EMPTY_CELL
+ *
+ * A step is shared along with the moves of the solution, so that the
+ * recipient sees the same solution whichever way it was found. Each move is
+ * its two endpoints, each as x and y (big endian u16), in the same encoding.
+ */
+const VERSION: u8 = 1;
+const HEADER_LENGTH: usize = 7;
+const EMPTY_CELL: u8 = 0xFE;
+const FLAG_OUTSIDE: u8 = 1 << 0;
+const FLAG_GRAVITY: u8 = 1 << 1;
+const FLAG_BONUS_CLASSES: u8 = 1 << 2;
+
+const BOARD_KEY: &str = "board";
+const STEP_KEY: &str = "step";
+const MOVES_KEY: &str = "moves";
+const MOVE_LENGTH: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShareError {
+    MissingBoard,
+    InvalidBase64,
+    UnsupportedVersion(u8),
+    InvalidLength,
+    InvalidStep,
+    InvalidMoves,
+}
+
+impl fmt::Display for ShareError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingBoard => write!(f, "The link has no board"),
+            Self::InvalidBase64 => write!(f, "The board in the link is not valid base64"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "Board layout version {} is not supported", version)
+            }
+            Self::InvalidLength => write!(f, "The board in the link is truncated"),
+            Self::InvalidStep => write!(f, "The step in the link is not a number"),
+            Self::InvalidMoves => write!(f, "The moves in the link do not solve the board"),
+        }
+    }
+}
+
+impl Error for ShareError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SharedBoard {
+    pub rows: usize,
+    pub cols: usize,
+    pub tiles: TileMap,
+    pub rules: RuleSet,
+    pub step: Option<usize>,
+    // The removals of the solution, only shared along with a step.
+    pub moves: Vec<[Coord; 2]>,
+}
+
+impl SharedBoard {
+    fn pack(&self) -> Option<Vec<u8>> {
+        if self
+            .tiles
+            .keys()
+            .any(|&[x, y]| x >= self.cols || y >= self.rows)
+        {
+            return None;
+        }
+        let [rows, cols] = [self.rows, self.cols].map(|v| u16::try_from(v).ok());
+        let mut flags = 0;
+        if self.rules.allow_outside {
+            flags |= FLAG_OUTSIDE;
+        }
+        if self.rules.gravity {
+            flags |= FLAG_GRAVITY;
+        }
+        /* Only the two presets of tile classes fit in the flags. */
+        match self.rules.classes {
+            classes if classes == create_bonus_tile_classes() => flags |= FLAG_BONUS_CLASSES,
+            classes if classes == TileClasses::default() => {}
+            _ => return None,
+        }
+
+        let mut bytes = vec![VERSION];
+        bytes.extend(rows?.to_be_bytes());
+        bytes.extend(cols?.to_be_bytes());
+        bytes.push(u8::try_from(self.rules.max_turns).ok()?);
+        bytes.push(flags);
+        for y in 0..self.rows {
+            for x in 0..self.cols {
+                bytes.push(match self.tiles.get(&[x, y]) {
+                    Some(&tile) if tile != EMPTY_CELL => tile,
+                    Some(_) => return None,
+                    None => EMPTY_CELL,
+                });
+            }
+        }
+        Some(bytes)
+    }
+
+    fn pack_moves(&self) -> Option<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(self.moves.len() * MOVE_LENGTH);
+        for coord in self.moves.iter().flatten() {
+            for v in coord {
+                bytes.extend(u16::try_from(*v).ok()?.to_be_bytes());
+            }
+        }
+        Some(bytes)
+    }
+
+    fn unpack_moves(bytes: &[u8]) -> Result<Vec<[Coord; 2]>, ShareError> {
+        if !bytes.len().is_multiple_of(MOVE_LENGTH) {
+            return Err(ShareError::InvalidMoves);
+        }
+        let read = |i: usize| u16::from_be_bytes([bytes[i], bytes[i + 1]]) as usize;
+        Ok((0..bytes.len())
+            .step_by(MOVE_LENGTH)
+            .map(|i| [[read(i), read(i + 2)], [read(i + 4), read(i + 6)]])
+            .collect())
+    }
+
+    fn unpack(bytes: &[u8]) -> Result<Self, ShareError> {
+        match bytes.first() {
+            Some(&VERSION) => {}
+            Some(&version) => return Err(ShareError::UnsupportedVersion(version)),
+            None => return Err(ShareError::InvalidLength),
+        }
+        if bytes.len() < HEADER_LENGTH {
+            return Err(ShareError::InvalidLength);
+        }
+
+        let rows = u16::from_be_bytes([bytes[1], bytes[2]]) as usize;
+        let cols = u16::from_be_bytes([bytes[3], bytes[4]]) as usize;
+        let flags = bytes[6];
+        let rules = RuleSet {
+            max_turns: bytes[5] as usize,
+            allow_outside: flags & FLAG_OUTSIDE != 0,
+            gravity: flags & FLAG_GRAVITY != 0,
+            classes: match flags & FLAG_BONUS_CLASSES != 0 {
+                true => create_bonus_tile_classes(),
+                false => TileClasses::default(),
+            },
+        };
+
+        let cells = &bytes[HEADER_LENGTH..];
+        if cells.len() != rows * cols {
+            return Err(ShareError::InvalidLength);
+        }
+        let tiles: HashMap<[usize; 2], Tile> = cells
+            .iter()
+            .enumerate()
+            .filter(|(_, &cell)| cell != EMPTY_CELL)
+            .map(|(i, &tile)| ([i % cols, i / cols], tile))
+            .collect();
+        Ok(Self {
+            rows,
+            cols,
+            tiles,
+            rules,
+            step: None,
+            moves: Vec::new(),
+        })
+    }
+
+    pub fn to_fragment(&self) -> Option<String> {
+        /* `None` is returned if the board, tiles included, does not fit the layout. */
+        let board = base64::encode_config(self.pack()?, base64::URL_SAFE_NO_PAD);
+        let mut fragment = format!("{}={}", BOARD_KEY, board);
+        if let Some(step) = self.step {
+            fragment.push_str(&format!("&{}={}", STEP_KEY, step));
+            if !self.moves.is_empty() {
+                let moves = base64::encode_config(self.pack_moves()?, base64::URL_SAFE_NO_PAD);
+                fragment.push_str(&format!("&{}={}", MOVES_KEY, moves));
+            }
+        }
+        Some(fragment)
+    }
+
+    pub fn from_fragment(fragment: &str) -> Result<Self, ShareError> {
+        let params: HashMap<&str, &str> = fragment
+            .trim_start_matches('#')
+            .split('&')
+            .filter_map(|param| param.split_once('='))
+            .collect();
+        let board = params.get(BOARD_KEY).ok_or(ShareError::MissingBoard)?;
+        let bytes = base64::decode_config(board, base64::URL_SAFE_NO_PAD)
+            .map_err(|_| ShareError::InvalidBase64)?;
+        let step = match params.get(STEP_KEY) {
+            Some(step) => Some(step.parse::<usize>().map_err(|_| ShareError::InvalidStep)?),
+            None => None,
+        };
+        let moves = match params.get(MOVES_KEY) {
+            Some(moves) => Self::unpack_moves(
+                &base64::decode_config(moves, base64::URL_SAFE_NO_PAD)
+                    .map_err(|_| ShareError::InvalidBase64)?,
+            )?,
+            None => Vec::new(),
+        };
+        Ok(Self {
+            step,
+            moves,
+            ..Self::unpack(&bytes)?
+        })
+    }
+
+    pub fn route_moves(&self, selection: PathSelection) -> Result<Vec<Nodes>, ShareError> {
+        route_moves(
+            &self.tiles,
+            &[self.cols, self.rows],
+            &self.moves,
+            &self.rules,
+            selection,
+        )
+        .ok_or(ShareError::InvalidMoves)
+    }
+}
+
+#[test]
+fn test_shared_board() {
+    use super::components::WALL;
+    use super::notation::parse_board;
+
+    let (tiles, _) = parse_board(
+        "
+        1m .  Pl
+        #  Sp 1m
+        ",
+    )
+    .unwrap();
+    let board = SharedBoard {
+        rows: 2,
+        cols: 3,
+        tiles,
+        rules: RuleSet {
+            max_turns: 3,
+            gravity: true,
+            classes: create_bonus_tile_classes(),
+            ..RuleSet::default()
+        },
+        step: None,
+        moves: Vec::new(),
+    };
+    let fragment = board.to_fragment().unwrap();
+    assert!(fragment.starts_with("board="));
+    assert!(!fragment.contains("step"));
+    assert_eq!(SharedBoard::from_fragment(&fragment), Ok(board.clone()));
+
+    let with_step = SharedBoard {
+        step: Some(4),
+        ..board.clone()
+    };
+    let fragment = format!("#{}", with_step.to_fragment().unwrap());
+    assert_eq!(SharedBoard::from_fragment(&fragment), Ok(with_step));
+
+    let walled = SharedBoard::from_fragment(&board.to_fragment().unwrap()).unwrap();
+    assert_eq!(walled.tiles.get(&[0, 1]), Some(&WALL));
+    assert_eq!(walled.tiles.get(&[1, 0]), None);
+
+    let outside = SharedBoard {
+        rows: 1,
+        ..board.clone()
+    };
+    assert_eq!(outside.to_fragment(), None);
+
+    let custom = SharedBoard {
+        rules: RuleSet {
+            classes: TileClasses::new(&[&[0, 1]]),
+            ..RuleSet::default()
+        },
+        ..board.clone()
+    };
+    assert_eq!(custom.to_fragment(), None);
+}
+
+#[test]
+fn test_shared_board_moves() {
+    use super::notation::parse_board;
+
+    let (tiles, _) = parse_board(
+        "
+        1m 2m 1m 2m
+        ",
+    )
+    .unwrap();
+    let board = SharedBoard {
+        rows: 2,
+        cols: 4,
+        tiles,
+        rules: RuleSet {
+            allow_outside: false,
+            ..RuleSet::default()
+        },
+        step: None,
+        moves: vec![[[0, 0], [2, 0]], [[1, 0], [3, 0]]],
+    };
+    let fragment = board.to_fragment().unwrap();
+    assert!(!fragment.contains("moves"));
+    assert!(SharedBoard::from_fragment(&fragment)
+        .unwrap()
+        .moves
+        .is_empty());
+
+    let with_step = SharedBoard {
+        step: Some(1),
+        ..board
+    };
+    let shared = SharedBoard::from_fragment(&with_step.to_fragment().unwrap()).unwrap();
+    assert_eq!(shared, with_step);
+    assert_eq!(
+        shared.route_moves(PathSelection::First),
+        Ok(vec![
+            vec![[0, 0], [0, 1], [2, 1], [2, 0]],
+            vec![[1, 0], [3, 0]],
+        ])
+    );
+
+    let mismatched = SharedBoard {
+        moves: vec![[[0, 0], [1, 0]]],
+        ..shared
+    };
+    assert_eq!(
+        mismatched.route_moves(PathSelection::First),
+        Err(ShareError::InvalidMoves)
+    );
+}
+
+#[test]
+fn test_shared_board_errors() {
+    assert_eq!(
+        SharedBoard::from_fragment("step=1"),
+        Err(ShareError::MissingBoard)
+    );
+    assert_eq!(
+        SharedBoard::from_fragment("board=!!"),
+        Err(ShareError::InvalidBase64)
+    );
+    assert_eq!(
+        SharedBoard::from_fragment("board=Ag"),
+        Err(ShareError::UnsupportedVersion(2))
+    );
+    assert_eq!(
+        SharedBoard::from_fragment("board=AQABAAI"),
+        Err(ShareError::InvalidLength)
+    );
+
+    let board = SharedBoard {
+        rows: 1,
+        cols: 2,
+        tiles: HashMap::new(),
+        rules: RuleSet::default(),
+        step: None,
+        moves: Vec::new(),
+    };
+    let fragment = format!("{}&step=x", board.to_fragment().unwrap());
+    assert_eq!(
+        SharedBoard::from_fragment(&fragment),
+        Err(ShareError::InvalidStep)
+    );
+    let fragment = format!("{}&step=1&moves=AAE", board.to_fragment().unwrap());
+    assert_eq!(
+        SharedBoard::from_fragment(&fragment),
+        Err(ShareError::InvalidMoves)
+    );
+}
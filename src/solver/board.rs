@@ -0,0 +1,333 @@
+use std::hash::{Hash, Hasher};
+
+use super::super::components::{Coord, CoordElement, Tile, TileMap, WALL};
+use super::utils::get_zobrist_key;
+
+const WORD_BITS: usize = 64;
+
+fn n_words(n_bits: usize) -> usize {
+    n_bits.div_ceil(WORD_BITS)
+}
+
+fn set_bit(words: &mut [u64], i: usize, value: bool) {
+    let mask = 1u64 << (i % WORD_BITS);
+    match value {
+        true => words[i / WORD_BITS] |= mask,
+        false => words[i / WORD_BITS] &= !mask,
+    }
+}
+
+fn is_range_empty(words: &[u64], from: usize, to: usize) -> bool {
+    /*
+     * Check bits in [from, to] (both inclusive) are all zero.
+     */
+    if from > to {
+        return true;
+    }
+
+    let (first, last) = (from / WORD_BITS, to / WORD_BITS);
+    (first..=last).all(|w| {
+        let lower = match w == first {
+            true => from % WORD_BITS,
+            false => 0,
+        };
+        let upper = match w == last {
+            true => to % WORD_BITS,
+            false => WORD_BITS - 1,
+        };
+        let mask = (u64::MAX >> (WORD_BITS - 1 - upper)) & (u64::MAX << lower);
+        words[w] & mask == 0
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoardKey {
+    // Compared first, so that the cells are only compared on a hash match.
+    hash: u64,
+    cells: Box<[Option<Tile>]>,
+}
+
+impl Hash for BoardKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.hash.hash(state);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Board {
+    size: [usize; 2],
+    cells: Vec<Option<Tile>>,
+    row_bits: Vec<u64>,
+    col_bits: Vec<u64>,
+    n_tiles: usize,
+    hash: u64,
+}
+
+impl Board {
+    pub fn new(size: &[usize; 2]) -> Self {
+        let [width, height] = *size;
+        Self {
+            size: *size,
+            cells: vec![None; width * height],
+            row_bits: vec![0; n_words(width) * height],
+            col_bits: vec![0; n_words(height) * width],
+            n_tiles: 0,
+            hash: 0,
+        }
+    }
+
+    pub fn from_map(map: &TileMap, size: &[usize; 2]) -> Self {
+        let mut board = Self::new(size);
+        for (coord, &tile) in map.iter() {
+            board.insert(coord, tile);
+        }
+        board
+    }
+
+    pub fn to_map(&self) -> TileMap {
+        self.cells().collect()
+    }
+
+    pub fn size(&self) -> [usize; 2] {
+        self.size
+    }
+
+    pub fn len(&self) -> usize {
+        self.n_tiles
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n_tiles == 0
+    }
+
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    pub fn key(&self) -> BoardKey {
+        /*
+         * The Zobrist hash alone can collide, so the key keeps the cells to
+         * tell such boards apart.
+         */
+        BoardKey {
+            hash: self.hash,
+            cells: self.cells.clone().into_boxed_slice(),
+        }
+    }
+
+    fn index(&self, &[x, y]: &Coord) -> Option<usize> {
+        match x < self.size[0] && y < self.size[1] {
+            true => Some(y * self.size[0] + x),
+            false => None,
+        }
+    }
+
+    pub fn get(&self, coord: &Coord) -> Option<Tile> {
+        self.index(coord).and_then(|i| self.cells[i])
+    }
+
+    pub fn contains(&self, coord: &Coord) -> bool {
+        self.get(coord).is_some()
+    }
+
+    pub fn is_wall(&self, coord: &Coord) -> bool {
+        self.get(coord) == Some(WALL)
+    }
+
+    fn set_occupied(&mut self, &[x, y]: &Coord, value: bool) {
+        let [width, height] = self.size;
+        let (row_words, col_words) = (n_words(width), n_words(height));
+        set_bit(
+            &mut self.row_bits[(y * row_words)..((y + 1) * row_words)],
+            x,
+            value,
+        );
+        set_bit(
+            &mut self.col_bits[(x * col_words)..((x + 1) * col_words)],
+            y,
+            value,
+        );
+    }
+
+    pub fn insert(&mut self, coord: &Coord, tile: Tile) -> Option<Tile> {
+        let i = self
+            .index(coord)
+            .expect("Coordinate is out of the board range.");
+        let previous = self.cells[i].replace(tile);
+        match previous {
+            Some(prev) => {
+                self.hash ^= get_zobrist_key(coord, prev);
+                if prev == WALL {
+                    self.n_tiles += 1;
+                }
+            }
+            None => {
+                self.n_tiles += 1;
+                self.set_occupied(coord, true);
+            }
+        }
+        if tile == WALL {
+            self.n_tiles -= 1;
+        }
+        self.hash ^= get_zobrist_key(coord, tile);
+        previous
+    }
+
+    pub fn remove(&mut self, coord: &Coord) -> Option<Tile> {
+        let removed = self.index(coord).and_then(|i| self.cells[i].take());
+        if let Some(tile) = removed {
+            if tile != WALL {
+                self.n_tiles -= 1;
+            }
+            self.hash ^= get_zobrist_key(coord, tile);
+            self.set_occupied(coord, false);
+        }
+        removed
+    }
+
+    fn cells(&self) -> impl Iterator<Item = (Coord, Tile)> + '_ {
+        let width = self.size[0];
+        self.cells
+            .iter()
+            .enumerate()
+            .filter_map(move |(i, cell)| cell.map(|tile| ([i % width, i / width], tile)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, Tile)> + '_ {
+        self.cells().filter(|&(_, tile)| tile != WALL)
+    }
+
+    pub fn walls(&self) -> impl Iterator<Item = Coord> + '_ {
+        self.cells()
+            .filter(|&(_, tile)| tile == WALL)
+            .map(|(coord, _)| coord)
+    }
+
+    pub fn is_row_empty(&self, y: CoordElement, x1: CoordElement, x2: CoordElement) -> bool {
+        /*
+         * Check cells from [x1, y] to [x2, y] (both inclusive) are all empty.
+         */
+        let row_words = n_words(self.size[0]);
+        let words = &self.row_bits[(y * row_words)..((y + 1) * row_words)];
+        is_range_empty(words, x1, x2)
+    }
+
+    pub fn is_col_empty(&self, x: CoordElement, y1: CoordElement, y2: CoordElement) -> bool {
+        /*
+         * Check cells from [x, y1] to [x, y2] (both inclusive) are all empty.
+         */
+        let col_words = n_words(self.size[1]);
+        let words = &self.col_bits[(x * col_words)..((x + 1) * col_words)];
+        is_range_empty(words, y1, y2)
+    }
+}
+
+#[test]
+fn test_is_range_empty() {
+    let words: Vec<u64> = vec![1 << 3 | 1 << 63, 1 << 2];
+
+    assert!(is_range_empty(&words, 0, 2));
+    assert!(!is_range_empty(&words, 0, 3));
+    assert!(is_range_empty(&words, 4, 62));
+    assert!(!is_range_empty(&words, 4, 63));
+    assert!(is_range_empty(&words, 64, 65));
+    assert!(!is_range_empty(&words, 64, 66));
+    assert!(!is_range_empty(&words, 60, 66));
+    assert!(is_range_empty(&words, 5, 4));
+}
+
+#[test]
+fn test_board() {
+    use super::utils::get_map_hash;
+    use std::collections::HashMap;
+    /*
+     * x 0 x 1
+     * 0 x x x
+     * x x 1 x
+     */
+    let map: TileMap = HashMap::from([([1, 0], 0), ([3, 0], 1), ([0, 1], 0), ([2, 2], 1)]);
+    let mut board = Board::from_map(&map, &[4, 3]);
+
+    assert_eq!(board.size(), [4, 3]);
+    assert_eq!(board.len(), 4);
+    assert_eq!(board.to_map(), map);
+    assert_eq!(board.hash(), get_map_hash(&map));
+    assert_eq!(board.get(&[3, 0]), Some(1));
+    assert_eq!(board.get(&[2, 0]), None);
+    assert_eq!(board.get(&[4, 0]), None);
+
+    assert!(board.is_row_empty(0, 2, 2));
+    assert!(!board.is_row_empty(0, 0, 1));
+    assert!(board.is_row_empty(1, 1, 3));
+    assert!(board.is_col_empty(2, 0, 1));
+    assert!(!board.is_col_empty(2, 0, 2));
+    assert!(board.is_col_empty(3, 1, 2));
+
+    assert_eq!(board.remove(&[2, 2]), Some(1));
+    assert_eq!(board.remove(&[2, 2]), None);
+    assert!(board.is_col_empty(2, 0, 2));
+    assert_eq!(board.len(), 3);
+
+    assert_eq!(board.insert(&[1, 0], 2), Some(0));
+    assert_eq!(board.len(), 3);
+    assert!(!board.is_row_empty(0, 1, 1));
+    assert_eq!(board.hash(), get_map_hash(&board.to_map()));
+}
+
+#[test]
+fn test_board_key() {
+    use std::collections::{HashMap, HashSet};
+    let map: TileMap = HashMap::from([([1, 0], 0), ([0, 1], 0)]);
+    let board = Board::from_map(&map, &[2, 2]);
+    assert_eq!(board.key(), Board::from_map(&map, &[2, 2]).key());
+
+    /* A board whose hash collides is still told apart by its cells. */
+    let collision = BoardKey {
+        hash: board.hash(),
+        cells: Board::new(&[2, 2]).cells.into_boxed_slice(),
+    };
+    let keys = HashSet::from([board.key()]);
+    assert!(keys.contains(&board.key()));
+    assert!(!keys.contains(&collision));
+}
+
+#[test]
+fn test_board_wide() {
+    let mut board = Board::new(&[70, 2]);
+    board.insert(&[65, 1], 0);
+
+    assert!(board.is_row_empty(1, 0, 64));
+    assert!(!board.is_row_empty(1, 0, 65));
+    assert!(board.is_row_empty(0, 0, 69));
+    assert!(!board.is_col_empty(65, 0, 1));
+    assert_eq!(board.iter().collect::<Vec<_>>(), vec![([65, 1], 0)]);
+}
+
+#[test]
+fn test_board_walls() {
+    use std::collections::HashMap;
+    /*
+     * 0 # 0
+     * # x x
+     */
+    let map: TileMap = HashMap::from([([0, 0], 0), ([1, 0], WALL), ([2, 0], 0), ([0, 1], WALL)]);
+    let mut board = Board::from_map(&map, &[3, 2]);
+
+    assert_eq!(board.len(), 2);
+    assert_eq!(board.to_map(), map);
+    assert!(board.is_wall(&[1, 0]));
+    assert!(!board.is_wall(&[0, 0]));
+    assert!(!board.is_row_empty(0, 1, 1));
+    assert!(!board.is_col_empty(0, 1, 1));
+    assert_eq!(board.walls().collect::<Vec<_>>(), vec![[1, 0], [0, 1]]);
+
+    let mut tiles: Vec<(Coord, Tile)> = board.iter().collect();
+    tiles.sort();
+    assert_eq!(tiles, vec![([0, 0], 0), ([2, 0], 0)]);
+
+    assert_eq!(board.insert(&[1, 0], 1), Some(WALL));
+    assert_eq!(board.len(), 3);
+    assert_eq!(board.remove(&[0, 1]), Some(WALL));
+    assert_eq!(board.len(), 3);
+    assert!(board.is_col_empty(0, 1, 1));
+}
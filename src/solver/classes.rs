@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+
+use super::super::components::Tile;
+
+const N_TILE_VALUES: usize = Tile::MAX as usize + 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "Vec<Tile>", try_from = "Vec<Tile>")]
+pub struct TileClasses {
+    classes: [Tile; N_TILE_VALUES],
+}
+
+impl From<TileClasses> for Vec<Tile> {
+    fn from(tile_classes: TileClasses) -> Self {
+        tile_classes.classes.to_vec()
+    }
+}
+
+impl TryFrom<Vec<Tile>> for TileClasses {
+    type Error = String;
+
+    fn try_from(classes: Vec<Tile>) -> Result<Self, Self::Error> {
+        match classes.try_into() {
+            Ok(classes) => Ok(Self { classes }),
+            Err(classes) => Err(format!(
+                "expected {} tile classes, got {}",
+                N_TILE_VALUES,
+                classes.len()
+            )),
+        }
+    }
+}
+
+impl Default for TileClasses {
+    fn default() -> Self {
+        let mut classes = [0; N_TILE_VALUES];
+        for (tile, class) in classes.iter_mut().enumerate() {
+            *class = tile as Tile;
+        }
+        Self { classes }
+    }
+}
+
+impl TileClasses {
+    pub fn new(groups: &[&[Tile]]) -> Self {
+        /*
+         * Every tile in a group is represented by the smallest tile of the
+         * group, and a tile in no group keeps a class of its own.
+         */
+        let mut tile_classes = Self::default();
+        for group in groups.iter() {
+            if let Some(&class) = group.iter().min() {
+                for &tile in group.iter() {
+                    tile_classes.classes[tile as usize] = class;
+                }
+            }
+        }
+        tile_classes
+    }
+
+    pub fn class(&self, tile: Tile) -> Tile {
+        self.classes[tile as usize]
+    }
+
+    pub fn is_matching(&self, tile1: Tile, tile2: Tile) -> bool {
+        self.class(tile1) == self.class(tile2)
+    }
+}
+
+#[test]
+fn test_tile_classes() {
+    let classes = TileClasses::default();
+    assert_eq!(classes.class(4), 4);
+    assert!(!classes.is_matching(4, 5));
+
+    let classes = TileClasses::new(&[&[7, 5, 6], &[10, 9]]);
+    assert_eq!(classes.class(6), 5);
+    assert_eq!(classes.class(10), 9);
+    assert_eq!(classes.class(8), 8);
+    assert!(classes.is_matching(7, 5));
+    assert!(!classes.is_matching(7, 9));
+
+    let restored = TileClasses::try_from(Vec::<Tile>::from(classes));
+    assert_eq!(restored, Ok(classes));
+    assert!(TileClasses::try_from(vec![0, 1, 2]).is_err());
+}
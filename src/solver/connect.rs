@@ -1,9 +1,20 @@
-use std::cmp::{max, min};
-
-use super::super::components::{
-    Coord, CoordDelta, CoordDeltaElement, CoordElement, Grid, Nodes, TileMap,
-};
-use super::direction::{RIGHT, UP, DOWN, LEFT};
+use std::cmp::{max, min, Reverse};
+use std::collections::BinaryHeap;
+
+use serde::{Deserialize, Serialize};
+
+use super::super::components::{Coord, CoordDelta, CoordDeltaElement, CoordElement, Grid, Nodes};
+use super::board::Board;
+use super::direction::{DOWN, LEFT, RIGHT, UP};
+use super::rules::RuleSet;
+use super::utils::{get_node_coords, get_node_length};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PathSelection {
+    #[default]
+    First,
+    Shortest,
+}
 
 fn move_coord(coord: &Coord, delta: &CoordDelta, map_size: &[usize; 2]) -> Option<Coord> {
     let moved_coord = [
@@ -18,21 +29,17 @@ fn move_coord(coord: &Coord, delta: &CoordDelta, map_size: &[usize; 2]) -> Optio
     }
 }
 
-fn get_grid(
-    &coord: &Coord,
-    delta: &CoordDelta,
-    map: &TileMap,
-    map_size: &[usize; 2],
-) -> Option<Grid> {
+fn get_grid(&coord: &Coord, delta: &CoordDelta, board: &Board) -> Option<Grid> {
+    let map_size = board.size();
     let mut cursor = coord.clone();
-    let mut next_cursor = move_coord(&coord, delta, map_size);
+    let mut next_cursor = move_coord(&coord, delta, &map_size);
 
     loop {
         match next_cursor {
-            Some(crd) => match map.get(&crd) {
+            Some(crd) => match board.get(&crd) {
                 None => {
                     cursor = next_cursor.unwrap();
-                    next_cursor = move_coord(&cursor, delta, map_size);
+                    next_cursor = move_coord(&cursor, delta, &map_size);
                 }
                 _ => break,
             },
@@ -51,11 +58,10 @@ fn get_grid_pair(
     coord2: &Coord,
     direction1: &CoordDelta,
     direction2: &CoordDelta,
-    map: &TileMap,
-    map_size: &[usize; 2],
+    board: &Board,
 ) -> Option<[Grid; 2]> {
-    let grid1 = get_grid(coord1, direction1, map, map_size);
-    let grid2 = get_grid(coord2, direction2, map, map_size);
+    let grid1 = get_grid(coord1, direction1, board);
+    let grid2 = get_grid(coord2, direction2, board);
     match (grid1, grid2) {
         (Some(g1), Some(g2)) => Some([g1, g2]),
         _ => None,
@@ -113,26 +119,25 @@ fn can_vertical_connect(
     y2: CoordElement,
     x: CoordElement,
     ignore_edge: bool,
-    map: &TileMap,
+    board: &Board,
 ) -> bool {
     let (ymin, ymax) = match y1 < y2 {
         true => (y1, y2),
         false => (y2, y1),
     };
     match ignore_edge {
-        true => (ymin + 1)..ymax,
-        false => ymin..(ymax + 1),
+        true => ymin + 1 > ymax || board.is_col_empty(x, ymin + 1, ymax - 1),
+        false => board.is_col_empty(x, ymin, ymax),
     }
-    .all(|y| !map.contains_key(&[x, y]))
 }
 
 fn explore_vertical_connection(
-    xrange: &[CoordElement; 2],
+    xs: impl Iterator<Item = CoordElement>,
     yaxis: &[CoordElement; 2],
-    map: &TileMap,
+    board: &Board,
 ) -> Option<Grid> {
-    for x in xrange[0]..=xrange[1] {
-        if can_vertical_connect(yaxis[0], yaxis[1], x, false, map) {
+    for x in xs {
+        if can_vertical_connect(yaxis[0], yaxis[1], x, false, board) {
             return Some([[x, yaxis[0]], [x, yaxis[1]]]);
         }
     }
@@ -144,26 +149,25 @@ fn can_horizontal_connect(
     x2: CoordElement,
     y: CoordElement,
     ignore_edge: bool,
-    map: &TileMap,
+    board: &Board,
 ) -> bool {
     let (xmin, xmax) = match x1 < x2 {
         true => (x1, x2),
         false => (x2, x1),
     };
     match ignore_edge {
-        true => (xmin + 1)..xmax,
-        false => xmin..(xmax + 1),
+        true => xmin + 1 > xmax || board.is_row_empty(y, xmin + 1, xmax - 1),
+        false => board.is_row_empty(y, xmin, xmax),
     }
-    .all(|x| !map.contains_key(&[x, y]))
 }
 
 fn explore_horizontal_connection(
-    yrange: &[CoordElement; 2],
+    ys: impl Iterator<Item = CoordElement>,
     xaxis: &[CoordElement; 2],
-    map: &TileMap,
+    board: &Board,
 ) -> Option<Grid> {
-    for y in yrange[0]..=yrange[1] {
-        if can_horizontal_connect(xaxis[0], xaxis[1], y, false, map) {
+    for y in ys {
+        if can_horizontal_connect(xaxis[0], xaxis[1], y, false, board) {
             return Some([[xaxis[0], y], [xaxis[1], y]]);
         }
     }
@@ -207,31 +211,28 @@ fn get_overwrapped_yrange(grid1: &Grid, grid2: &Grid) -> Option<[CoordElement; 2
 fn try_get_double_node_connection(
     &coord1: &Coord,
     &coord2: &Coord,
-    map: &TileMap,
+    board: &Board,
 ) -> Option<Nodes> {
     let nodes = vec![coord1, coord2];
     if is_tile_adjacent(&coord1, &coord2) {
         return Some(nodes);
     }
 
-    if coord1[0] == coord2[0] && can_vertical_connect(coord1[1], coord2[1], coord1[0], true, map)
+    if coord1[0] == coord2[0] && can_vertical_connect(coord1[1], coord2[1], coord1[0], true, board)
     {
         return Some(nodes);
     }
 
-    if coord1[1] == coord2[1] && can_horizontal_connect(coord1[0], coord2[0], coord1[1], true, map) {
+    if coord1[1] == coord2[1]
+        && can_horizontal_connect(coord1[0], coord2[0], coord1[1], true, board)
+    {
         return Some(nodes);
     }
 
     None
 }
 
-fn try_get_triple_node_connection(
-    coord1: &Coord,
-    coord2: &Coord,
-    map: &TileMap,
-    map_size: &[usize; 2],
-) -> Option<Nodes> {
+fn try_get_triple_node_connection(coord1: &Coord, coord2: &Coord, board: &Board) -> Option<Nodes> {
     let (h_dir1, h_dir2) = match coord1[0] > coord2[0] {
         true => (LEFT, RIGHT),
         false => (RIGHT, LEFT),
@@ -242,7 +243,7 @@ fn try_get_triple_node_connection(
     };
 
     for (dir1, dir2) in [(h_dir1, v_dir2), (v_dir1, h_dir2)].iter() {
-        if let Some([grid1, grid2]) = get_grid_pair(coord1, coord2, dir1, dir2, map, map_size) {
+        if let Some([grid1, grid2]) = get_grid_pair(coord1, coord2, dir1, dir2, board) {
             if let Some(intersection) = get_intersection(&grid1, &grid2) {
                 return Some(vec![coord1.to_owned(), intersection, coord2.to_owned()]);
             }
@@ -252,103 +253,233 @@ fn try_get_triple_node_connection(
     None
 }
 
+fn get_range_order(
+    range: &[CoordElement; 2],
+    axis: &[CoordElement; 2],
+    selection: PathSelection,
+) -> Vec<CoordElement> {
+    let mut values: Vec<CoordElement> = (range[0]..=range[1]).collect();
+    if selection == PathSelection::Shortest {
+        let [a, b] = *axis;
+        values.sort_by_key(|&v| v.abs_diff(a) + v.abs_diff(b));
+    }
+    values
+}
+
 fn try_get_quadro_node_connection(
     coord1: &Coord,
     coord2: &Coord,
-    map: &TileMap,
-    map_size: &[usize; 2],
+    board: &Board,
+    selection: PathSelection,
 ) -> Option<Nodes> {
     let xaxis = [coord1[0], coord2[0]];
     let yaxis = [coord1[1], coord2[1]];
+    let h_dirs = match coord1[0] > coord2[0] {
+        true => (LEFT, RIGHT),
+        false => (RIGHT, LEFT),
+    };
+    let v_dirs = match coord1[1] > coord2[1] {
+        true => (UP, DOWN),
+        false => (DOWN, UP),
+    };
 
     /*
-     * o - -   - - o
-     * x x x   x x x
-     * x o -   - o x
+     * o - -   - - o   | x |   o x x   - - o   | x o
+     * x x x   x x x   o x |   | x o   x x x   | x |
+     * x o -   - o x   x x o   | x |   o - -   o x |
      */
-    for direction in [LEFT, RIGHT].iter() {
-        let grids = get_grid_pair(coord1, coord2, direction, direction, map, map_size);
-        if let Some([grid1, grid2]) = grids {
-            if let Some(xrange) = get_overwrapped_xrange(&grid1, &grid2) {
-                let grid = explore_vertical_connection(&xrange, &yaxis, map);
-                if let Some([coord1b, coord2b]) = grid {
-                    return Some(vec![coord1.to_owned(), coord1b, coord2b, coord2.to_owned()]);
-                }
+    let mut connections = [
+        (LEFT, LEFT),
+        (RIGHT, RIGHT),
+        (UP, UP),
+        (DOWN, DOWN),
+        h_dirs,
+        v_dirs,
+    ]
+    .into_iter()
+    .filter_map(|(dir1, dir2)| {
+        let [grid1, grid2] = get_grid_pair(coord1, coord2, &dir1, &dir2, board)?;
+        let [coord1b, coord2b] = match dir1[1] == 0 {
+            true => {
+                let xrange = get_overwrapped_xrange(&grid1, &grid2)?;
+                let xs = get_range_order(&xrange, &xaxis, selection);
+                explore_vertical_connection(xs.into_iter(), &yaxis, board)?
             }
-        }
+            false => {
+                let yrange = get_overwrapped_yrange(&grid1, &grid2)?;
+                let ys = get_range_order(&yrange, &yaxis, selection);
+                explore_horizontal_connection(ys.into_iter(), &xaxis, board)?
+            }
+        };
+        Some(vec![coord1.to_owned(), coord1b, coord2b, coord2.to_owned()])
+    });
+
+    match selection {
+        PathSelection::First => connections.next(),
+        PathSelection::Shortest => connections.min_by_key(get_node_length),
     }
+}
 
+fn try_get_searched_node_connection(
+    coord1: &Coord,
+    coord2: &Coord,
+    board: &Board,
+    max_turns: usize,
+) -> Option<Nodes> {
     /*
-     * | x |   o x x
-     * o x |   | x o
-     * x x o   | x |
+     * Dijkstra over (cell, direction) ordered by (turns, length), so the
+     * first time coord2 is reached the path has the fewest turns and is the
+     * shortest among them.
      */
-    for direction in [UP, DOWN].iter() {
-        let grids = get_grid_pair(coord1, coord2, direction, direction, map, map_size);
-        if let Some([grid1, grid2]) = grids {
-            if let Some(yrange) = get_overwrapped_yrange(&grid1, &grid2) {
-                let grid = explore_horizontal_connection(&yrange, &xaxis, map);
-                if let Some([coord1b, coord2b]) = grid {
-                    return Some(vec![coord1.to_owned(), coord1b, coord2b, coord2.to_owned()]);
-                }
-            }
+    let directions = [RIGHT, DOWN, LEFT, UP];
+    let map_size = board.size();
+    let index = |&[x, y]: &Coord, dir: usize| (y * map_size[0] + x) * directions.len() + dir;
+
+    let mut parents: Vec<Option<Option<(Coord, usize)>>> =
+        vec![None; map_size[0] * map_size[1] * directions.len()];
+    let mut queue = BinaryHeap::new();
+    for (dir, delta) in directions.iter().enumerate() {
+        if let Some(next) = move_coord(coord1, delta, &map_size) {
+            queue.push(Reverse((0, 1, next, dir, None)));
         }
     }
 
-    /*
-     * - - o
-     * x x x
-     * o - -
-     */
-    if let Some([grid1, grid2]) = match coord1[0] > coord2[0] {
-        true => get_grid_pair(coord1, coord2, &LEFT, &RIGHT, map, map_size),
-        false => get_grid_pair(coord1, coord2, &RIGHT, &LEFT, map, map_size),
-    } {
-        if let Some(xrange) = get_overwrapped_xrange(&grid1, &grid2) {
-            let grid = explore_vertical_connection(&xrange, &yaxis, map);
-            if let Some([coord1b, coord2b]) = grid {
-                return Some(vec![coord1.to_owned(), coord1b, coord2b, coord2.to_owned()]);
+    while let Some(Reverse((turns, length, coord, dir, parent))) = queue.pop() {
+        if (&coord != coord2 && board.contains(&coord)) || parents[index(&coord, dir)].is_some() {
+            continue;
+        }
+        parents[index(&coord, dir)] = Some(parent);
+
+        if &coord == coord2 {
+            let mut nodes = vec![coord];
+            let (mut cursor, mut cursor_dir) = (coord, dir);
+            while let Some((parent, parent_dir)) = parents[index(&cursor, cursor_dir)].flatten() {
+                if parent_dir != cursor_dir {
+                    nodes.push(parent);
+                }
+                (cursor, cursor_dir) = (parent, parent_dir);
             }
+            nodes.push(*coord1);
+            nodes.reverse();
+            return Some(nodes);
         }
-    }
 
-    /*
-     * | x o
-     * | x |
-     * o x |
-     */
-    if let Some([grid1, grid2]) = match coord1[1] > coord2[1] {
-        true => get_grid_pair(coord1, coord2, &UP, &DOWN, map, map_size),
-        false => get_grid_pair(coord1, coord2, &DOWN, &UP, map, map_size),
-    } {
-        if let Some(yrange) = get_overwrapped_yrange(&grid1, &grid2) {
-            let grid = explore_horizontal_connection(&yrange, &xaxis, map);
-            if let Some([coord1b, coord2b]) = grid {
-                return Some(vec![coord1.to_owned(), coord1b, coord2b, coord2.to_owned()]);
+        for next_dir in 0..directions.len() {
+            let next_turns = turns + (next_dir != dir) as usize;
+            if (next_dir + 2) % directions.len() == dir || next_turns > max_turns {
+                continue;
+            }
+            if let Some(next) = move_coord(&coord, &directions[next_dir], &map_size) {
+                queue.push(Reverse((
+                    next_turns,
+                    length + 1,
+                    next,
+                    next_dir,
+                    Some((coord, dir)),
+                )));
             }
         }
     }
-
     None
 }
 
 pub fn try_get_node_connection(
     coord1: &Coord,
     coord2: &Coord,
-    map: &TileMap,
-    map_size: &[usize; 2],
+    board: &Board,
+    rules: &RuleSet,
+    selection: PathSelection,
 ) -> Option<Nodes> {
-    let double_node_connection = try_get_double_node_connection(coord1, coord2, map);
-    if double_node_connection != None {
+    if rules.max_turns > 2 {
+        return try_get_searched_node_connection(coord1, coord2, board, rules.max_turns);
+    }
+
+    let double_node_connection = try_get_double_node_connection(coord1, coord2, board);
+    if double_node_connection.is_some() || rules.max_turns < 1 {
         return double_node_connection;
     }
 
-    let triple_node_connection = try_get_triple_node_connection(coord1, coord2, map, map_size);
-    if triple_node_connection != None {
+    let triple_node_connection = try_get_triple_node_connection(coord1, coord2, board);
+    if triple_node_connection.is_some() || rules.max_turns < 2 {
         return triple_node_connection;
     }
 
-    try_get_quadro_node_connection(coord1, coord2, map, map_size)
+    try_get_quadro_node_connection(coord1, coord2, board, selection)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeConnection {
+    pub nodes: Nodes,
+    pub turns: usize,
+    pub length: usize,
+}
+
+fn is_path_clear(nodes: &Nodes, board: &Board) -> bool {
+    let coords = get_node_coords(nodes);
+    let [width, height] = board.size();
+    coords.len() >= 2
+        && coords[1..(coords.len() - 1)]
+            .iter()
+            .all(|&[x, y]| x < width && y < height && !board.contains(&[x, y]))
+}
+
+pub fn get_all_node_connections(
+    coord1: &Coord,
+    coord2: &Coord,
+    board: &Board,
+    rules: &RuleSet,
+) -> Vec<NodeConnection> {
+    /*
+     * Try every path with at most two turns instead of following grids, so
+     * that the result does not depend on the cases in try_get_node_connection.
+     * `board` is the one from `rules.create_board`, so it only has a margin
+     * around the tiles when `allow_outside` is set, and paths stay within it.
+     */
+    let (&[x1, y1], &[x2, y2]) = (coord1, coord2);
+    let [width, height] = board.size();
+    let mut candidates: Vec<Nodes> = Vec::new();
+
+    if coord1 == coord2 {
+        return Vec::new();
+    }
+
+    if x1 == x2 || y1 == y2 {
+        candidates.push(vec![*coord1, *coord2]);
+    }
+
+    if x1 != x2 && y1 != y2 {
+        candidates.push(vec![*coord1, [x2, y1], *coord2]);
+        candidates.push(vec![*coord1, [x1, y2], *coord2]);
+    }
+
+    if y1 != y2 {
+        candidates.extend(
+            (0..width)
+                .filter(|&x| x != x1 && x != x2)
+                .map(|x| vec![*coord1, [x, y1], [x, y2], *coord2]),
+        );
+    }
+
+    if x1 != x2 {
+        candidates.extend(
+            (0..height)
+                .filter(|&y| y != y1 && y != y2)
+                .map(|y| vec![*coord1, [x1, y], [x2, y], *coord2]),
+        );
+    }
+
+    let mut connections: Vec<NodeConnection> = candidates
+        .into_iter()
+        .filter(|nodes| nodes.len() - 2 <= rules.max_turns)
+        .filter(|nodes| is_path_clear(nodes, board))
+        .map(|nodes| NodeConnection {
+            turns: nodes.len() - 2,
+            length: get_node_length(&nodes),
+            nodes,
+        })
+        .collect();
+    connections.sort_by(|a, b| (a.turns, a.length, &a.nodes).cmp(&(b.turns, b.length, &b.nodes)));
+    connections
 }
 
 #[test]
@@ -365,6 +496,7 @@ fn test_move_coord() {
 
 #[test]
 fn test_get_grid() {
+    use super::super::components::TileMap;
     use std::collections::HashMap;
     /* tile map:
      * x 0 x 0
@@ -373,43 +505,20 @@ fn test_get_grid() {
      */
     let map: TileMap = HashMap::from([([0, 1], 0), ([0, 3], 0), ([1, 0], 0), ([2, 3], 0)]);
     let map_size = [3, 4];
-
-    assert_eq!(get_grid(&[0, 2], &UP, &map, &map_size), None);
-    assert_eq!(get_grid(&[0, 2], &DOWN, &map, &map_size), None);
-    assert_eq!(get_grid(&[1, 3], &LEFT, &map, &map_size), None);
-    assert_eq!(get_grid(&[1, 3], &RIGHT, &map, &map_size), None);
-    assert_eq!(
-        get_grid(&[0, 3], &UP, &map, &map_size),
-        Some([[0, 3], [0, 2]])
-    );
-    assert_eq!(
-        get_grid(&[0, 1], &DOWN, &map, &map_size),
-        Some([[0, 1], [0, 2]])
-    );
-    assert_eq!(
-        get_grid(&[2, 3], &LEFT, &map, &map_size),
-        Some([[2, 3], [1, 3]])
-    );
-    assert_eq!(
-        get_grid(&[0, 3], &RIGHT, &map, &map_size),
-        Some([[0, 3], [1, 3]])
-    );
-    assert_eq!(
-        get_grid(&[1, 2], &UP, &map, &map_size),
-        Some([[1, 2], [1, 1]])
-    );
-    assert_eq!(
-        get_grid(&[1, 2], &DOWN, &map, &map_size),
-        Some([[1, 2], [1, 3]])
-    );
-    assert_eq!(
-        get_grid(&[1, 2], &LEFT, &map, &map_size),
-        Some([[1, 2], [0, 2]])
-    );
-    assert_eq!(
-        get_grid(&[1, 2], &RIGHT, &map, &map_size),
-        Some([[1, 2], [2, 2]])
-    );
+    let board = Board::from_map(&map, &map_size);
+
+    assert_eq!(get_grid(&[0, 2], &UP, &board), None);
+    assert_eq!(get_grid(&[0, 2], &DOWN, &board), None);
+    assert_eq!(get_grid(&[1, 3], &LEFT, &board), None);
+    assert_eq!(get_grid(&[1, 3], &RIGHT, &board), None);
+    assert_eq!(get_grid(&[0, 3], &UP, &board), Some([[0, 3], [0, 2]]));
+    assert_eq!(get_grid(&[0, 1], &DOWN, &board), Some([[0, 1], [0, 2]]));
+    assert_eq!(get_grid(&[2, 3], &LEFT, &board), Some([[2, 3], [1, 3]]));
+    assert_eq!(get_grid(&[0, 3], &RIGHT, &board), Some([[0, 3], [1, 3]]));
+    assert_eq!(get_grid(&[1, 2], &UP, &board), Some([[1, 2], [1, 1]]));
+    assert_eq!(get_grid(&[1, 2], &DOWN, &board), Some([[1, 2], [1, 3]]));
+    assert_eq!(get_grid(&[1, 2], &LEFT, &board), Some([[1, 2], [0, 2]]));
+    assert_eq!(get_grid(&[1, 2], &RIGHT, &board), Some([[1, 2], [2, 2]]));
 }
 
 #[test]
@@ -499,8 +608,10 @@ fn test_get_intersection() {
 
 #[test]
 fn test_explore_vartical_connection() {
+    use super::super::components::TileMap;
     use std::collections::HashMap;
     let map1: TileMap = HashMap::from([([1, 1], 0), ([3, 1], 0)]);
+    let board1 = Board::from_map(&map1, &[4, 3]);
     /* tile map:
      * | x x
      * | 0 |
@@ -508,32 +619,36 @@ fn test_explore_vartical_connection() {
      * | 0 |
      */
     assert_eq!(
-        explore_vertical_connection(&[1, 3], &[0, 2], &map1),
+        explore_vertical_connection(1..=3, &[0, 2], &board1),
         Some([[2, 0], [2, 2]])
     );
-    assert_eq!(explore_vertical_connection(&[1, 1], &[0, 2], &map1), None);
+    assert_eq!(explore_vertical_connection(1..=1, &[0, 2], &board1), None);
 
     let map2: TileMap = HashMap::from([([1, 1], 0), ([2, 1], 0), ([3, 1], 0)]);
-    assert_eq!(explore_vertical_connection(&[1, 3], &[0, 2], &map2), None);
+    let board2 = Board::from_map(&map2, &[4, 3]);
+    assert_eq!(explore_vertical_connection(1..=3, &[0, 2], &board2), None);
 }
 
 #[test]
 fn test_explore_horizontal_connection() {
+    use super::super::components::TileMap;
     use std::collections::HashMap;
     let map1: TileMap = HashMap::from([([1, 1], 0), ([1, 3], 0)]);
+    let board1 = Board::from_map(&map1, &[3, 4]);
     /* tile map:
      * -------
      * x 0 x 0
      * x -----
      */
     assert_eq!(
-        explore_horizontal_connection(&[1, 3], &[0, 2], &map1),
+        explore_horizontal_connection(1..=3, &[0, 2], &board1),
         Some([[0, 2], [2, 2]])
     );
-    assert_eq!(explore_horizontal_connection(&[1, 1], &[0, 2], &map1), None);
+    assert_eq!(explore_horizontal_connection(1..=1, &[0, 2], &board1), None);
 
     let map2: TileMap = HashMap::from([([1, 1], 0), ([1, 2], 0), ([1, 3], 0)]);
-    assert_eq!(explore_horizontal_connection(&[1, 3], &[0, 2], &map2), None);
+    let board2 = Board::from_map(&map2, &[3, 4]);
+    assert_eq!(explore_horizontal_connection(1..=3, &[0, 2], &board2), None);
 }
 
 #[test]
@@ -597,6 +712,7 @@ fn test_get_overlapped_yrange() {
 
 #[test]
 fn test_try_get_double_node_connection() {
+    use super::super::components::TileMap;
     use std::collections::HashMap;
     /*
      * x x x
@@ -604,30 +720,41 @@ fn test_try_get_double_node_connection() {
      * x x x
      */
     let map: TileMap = HashMap::from([([1, 1], 0)]);
+    let board = Board::from_map(&map, &[3, 3]);
 
     assert_eq!(
-        try_get_double_node_connection(&[0, 0], &[0, 2], &map),
+        try_get_double_node_connection(&[0, 0], &[0, 2], &board),
         Some(vec![[0, 0], [0, 2]])
     );
     assert_eq!(
-        try_get_double_node_connection(&[0, 0], &[2, 0], &map),
+        try_get_double_node_connection(&[0, 0], &[2, 0], &board),
         Some(vec![[0, 0], [2, 0]])
     );
     assert_eq!(
-        try_get_double_node_connection(&[0, 0], &[0, 1], &map),
+        try_get_double_node_connection(&[0, 0], &[0, 1], &board),
         Some(vec![[0, 0], [0, 1]])
     );
     assert_eq!(
-        try_get_double_node_connection(&[0, 0], &[1, 0], &map),
+        try_get_double_node_connection(&[0, 0], &[1, 0], &board),
         Some(vec![[0, 0], [1, 0]])
     );
-    assert_eq!(try_get_double_node_connection(&[1, 0], &[1, 2], &map), None);
-    assert_eq!(try_get_double_node_connection(&[0, 1], &[2, 1], &map), None);
-    assert_eq!(try_get_double_node_connection(&[0, 0], &[2, 2], &map), None);
+    assert_eq!(
+        try_get_double_node_connection(&[1, 0], &[1, 2], &board),
+        None
+    );
+    assert_eq!(
+        try_get_double_node_connection(&[0, 1], &[2, 1], &board),
+        None
+    );
+    assert_eq!(
+        try_get_double_node_connection(&[0, 0], &[2, 2], &board),
+        None
+    );
 }
 
 #[test]
 fn test_try_get_triple_node_connection() {
+    use super::super::components::TileMap;
     use std::collections::HashMap;
     /*
      * x x x
@@ -636,21 +763,22 @@ fn test_try_get_triple_node_connection() {
      */
     let map1: TileMap = HashMap::from([([1, 1], 0), ([2, 2], 0)]);
     let map_size = [3, 3];
+    let board1 = Board::from_map(&map1, &map_size);
 
     assert_eq!(
-        try_get_triple_node_connection(&[0, 1], &[1, 0], &map1, &map_size),
+        try_get_triple_node_connection(&[0, 1], &[1, 0], &board1),
         Some(vec![[0, 1], [0, 0], [1, 0]])
     );
     assert_eq!(
-        try_get_triple_node_connection(&[0, 2], &[2, 0], &map1, &map_size),
+        try_get_triple_node_connection(&[0, 2], &[2, 0], &board1),
         Some(vec![[0, 2], [0, 0], [2, 0]])
     );
     assert_eq!(
-        try_get_triple_node_connection(&[1, 2], &[2, 1], &map1, &map_size),
+        try_get_triple_node_connection(&[1, 2], &[2, 1], &board1),
         None
     );
     assert_eq!(
-        try_get_triple_node_connection(&[1, 1], &[2, 2], &map1, &map_size),
+        try_get_triple_node_connection(&[1, 1], &[2, 2], &board1),
         Some(vec![[1, 1], [2, 1], [2, 2]])
     );
 
@@ -660,16 +788,17 @@ fn test_try_get_triple_node_connection() {
      * x x x
      */
     let map2: TileMap = HashMap::from([([0, 0], 0), ([1, 1], 0)]);
+    let board2 = Board::from_map(&map2, &map_size);
     assert_eq!(
-        try_get_triple_node_connection(&[0, 1], &[1, 0], &map2, &map_size),
+        try_get_triple_node_connection(&[0, 1], &[1, 0], &board2),
         None
     );
     assert_eq!(
-        try_get_triple_node_connection(&[0, 2], &[2, 0], &map2, &map_size),
+        try_get_triple_node_connection(&[0, 2], &[2, 0], &board2),
         Some(vec![[0, 2], [2, 2], [2, 0]])
     );
     assert_eq!(
-        try_get_triple_node_connection(&[1, 2], &[2, 1], &map2, &map_size),
+        try_get_triple_node_connection(&[1, 2], &[2, 1], &board2),
         Some(vec![[1, 2], [2, 2], [2, 1]])
     );
 }
@@ -689,148 +818,161 @@ fn test_try_get_quadro_node_connection() {
     };
 
     let map1 = HashMap::from([([0, 0], 0), ([0, 1], 1), ([1, 1], 1), ([1, 2], 0)]);
+    let board1 = Board::from_map(&map1, &map_size);
     let (coord1a, coord1b) = ([0, 0], [1, 2]);
     let expected1 = Some(vec![[0, 0], [2, 0], [2, 2], [1, 2]]);
     assert_eq!(
-        try_get_quadro_node_connection(&coord1a, &coord1b, &map1, &map_size),
+        try_get_quadro_node_connection(&coord1a, &coord1b, &board1, PathSelection::First),
         expected1
     );
     assert_eq!(
-        try_get_quadro_node_connection(&coord1b, &coord1a, &map1, &map_size),
+        try_get_quadro_node_connection(&coord1b, &coord1a, &board1, PathSelection::First),
         reverse(expected1)
     );
 
     let map2 = HashMap::from([([1, 1], 1), ([1, 2], 0), ([2, 0], 0), ([2, 1], 1)]);
+    let board2 = Board::from_map(&map2, &map_size);
     let (coord2a, coord2b) = ([2, 0], [1, 2]);
     let expected2 = Some(vec![[2, 0], [0, 0], [0, 2], [1, 2]]);
     assert_eq!(
-        try_get_quadro_node_connection(&coord2a, &coord2b, &map2, &map_size),
+        try_get_quadro_node_connection(&coord2a, &coord2b, &board2, PathSelection::First),
         expected2
     );
     assert_eq!(
-        try_get_quadro_node_connection(&coord2b, &coord2a, &map2, &map_size),
+        try_get_quadro_node_connection(&coord2b, &coord2a, &board2, PathSelection::First),
         reverse(expected2)
     );
 
     let map3 = HashMap::from([([0, 0], 0), ([1, 0], 1), ([1, 1], 1), ([2, 1], 0)]);
+    let board3 = Board::from_map(&map3, &map_size);
     let (coord3a, coord3b) = ([0, 0], [2, 1]);
     let expected3 = Some(vec![[0, 0], [0, 2], [2, 2], [2, 1]]);
     assert_eq!(
-        try_get_quadro_node_connection(&coord3a, &coord3b, &map3, &map_size),
+        try_get_quadro_node_connection(&coord3a, &coord3b, &board3, PathSelection::First),
         expected3
     );
     assert_eq!(
-        try_get_quadro_node_connection(&coord3b, &coord3a, &map3, &map_size),
+        try_get_quadro_node_connection(&coord3b, &coord3a, &board3, PathSelection::First),
         reverse(expected3)
     );
 
     let map4 = HashMap::from([([0, 2], 0), ([1, 1], 1), ([1, 2], 1), ([2, 1], 0)]);
+    let board4 = Board::from_map(&map4, &map_size);
     let (coord4a, coord4b) = ([0, 2], [2, 1]);
     let expected4 = Some(vec![[0, 2], [0, 0], [2, 0], [2, 1]]);
     assert_eq!(
-        try_get_quadro_node_connection(&coord4a, &coord4b, &map4, &map_size),
+        try_get_quadro_node_connection(&coord4a, &coord4b, &board4, PathSelection::First),
         expected4
     );
     assert_eq!(
-        try_get_quadro_node_connection(&coord4b, &coord4a, &map4, &map_size),
+        try_get_quadro_node_connection(&coord4b, &coord4a, &board4, PathSelection::First),
         reverse(expected4)
     );
 
     let map5 = HashMap::from([([0, 2], 0), ([1, 0], 1), ([1, 2], 1), ([2, 0], 0)]);
+    let board5 = Board::from_map(&map5, &map_size);
     let (coord5a, coord5b) = ([0, 2], [2, 0]);
     let expected5 = Some(vec![[0, 2], [0, 1], [2, 1], [2, 0]]);
     assert_eq!(
-        try_get_quadro_node_connection(&coord5a, &coord5b, &map5, &map_size),
+        try_get_quadro_node_connection(&coord5a, &coord5b, &board5, PathSelection::First),
         expected5
     );
     assert_eq!(
-        try_get_quadro_node_connection(&coord5b, &coord5a, &map5, &map_size),
+        try_get_quadro_node_connection(&coord5b, &coord5a, &board5, PathSelection::First),
         reverse(expected5)
     );
 
     let map6 = HashMap::from([([0, 0], 0), ([1, 0], 1), ([1, 2], 1), ([2, 2], 0)]);
+    let board6 = Board::from_map(&map6, &map_size);
     let (coord6a, coord6b) = ([0, 0], [2, 2]);
     let expected6 = Some(vec![[0, 0], [0, 1], [2, 1], [2, 2]]);
     assert_eq!(
-        try_get_quadro_node_connection(&coord6a, &coord6b, &map6, &map_size),
+        try_get_quadro_node_connection(&coord6a, &coord6b, &board6, PathSelection::First),
         expected6
     );
     assert_eq!(
-        try_get_quadro_node_connection(&coord6b, &coord6a, &map6, &map_size),
+        try_get_quadro_node_connection(&coord6b, &coord6a, &board6, PathSelection::First),
         reverse(expected6)
     );
 
     let map7 = HashMap::from([([0, 2], 0), ([0, 1], 1), ([2, 1], 1), ([2, 0], 0)]);
+    let board7 = Board::from_map(&map7, &map_size);
     let (coord7a, coord7b) = ([0, 2], [2, 0]);
     let expected7 = Some(vec![[0, 2], [1, 2], [1, 0], [2, 0]]);
     assert_eq!(
-        try_get_quadro_node_connection(&coord7a, &coord7b, &map7, &map_size),
+        try_get_quadro_node_connection(&coord7a, &coord7b, &board7, PathSelection::First),
         expected7
     );
     assert_eq!(
-        try_get_quadro_node_connection(&coord7b, &coord7a, &map7, &map_size),
+        try_get_quadro_node_connection(&coord7b, &coord7a, &board7, PathSelection::First),
         reverse(expected7)
     );
 
     let map8 = HashMap::from([([0, 0], 0), ([0, 1], 1), ([2, 1], 1), ([2, 2], 0)]);
+    let board8 = Board::from_map(&map8, &map_size);
     let (coord8a, coord8b) = ([0, 0], [2, 2]);
     let expected8 = Some(vec![[0, 0], [1, 0], [1, 2], [2, 2]]);
     assert_eq!(
-        try_get_quadro_node_connection(&coord8a, &coord8b, &map8, &map_size),
+        try_get_quadro_node_connection(&coord8a, &coord8b, &board8, PathSelection::First),
         expected8
     );
     assert_eq!(
-        try_get_quadro_node_connection(&coord8b, &coord8a, &map8, &map_size),
+        try_get_quadro_node_connection(&coord8b, &coord8a, &board8, PathSelection::First),
         reverse(expected8)
     );
 
     let map9 = HashMap::from([([0, 0], 1), ([0, 1], 0), ([1, 0], 0), ([1, 1], 1)]);
+    let board9 = Board::from_map(&map9, &map_size);
     let (coord9a, coord9b) = ([0, 0], [1, 1]);
     assert_eq!(
-        try_get_quadro_node_connection(&coord9a, &coord9b, &map9, &map_size),
+        try_get_quadro_node_connection(&coord9a, &coord9b, &board9, PathSelection::First),
         None
     );
     assert_eq!(
-        try_get_quadro_node_connection(&coord9b, &coord9a, &map9, &map_size),
+        try_get_quadro_node_connection(&coord9b, &coord9a, &board9, PathSelection::First),
         None
     );
 
     let map10 = HashMap::from([([0, 1], 0), ([0, 2], 1), ([1, 1], 1), ([1, 2], 0)]);
+    let board10 = Board::from_map(&map10, &map_size);
     let (coord10a, coord10b) = ([0, 1], [1, 2]);
     assert_eq!(
-        try_get_quadro_node_connection(&coord10a, &coord10b, &map10, &map_size),
+        try_get_quadro_node_connection(&coord10a, &coord10b, &board10, PathSelection::First),
         None
     );
     assert_eq!(
-        try_get_quadro_node_connection(&coord10b, &coord10a, &map10, &map_size),
+        try_get_quadro_node_connection(&coord10b, &coord10a, &board10, PathSelection::First),
         None
     );
 
     let map11 = HashMap::from([([1, 0], 0), ([1, 1], 1), ([2, 0], 1), ([2, 1], 0)]);
+    let board11 = Board::from_map(&map11, &map_size);
     let (coord11a, coord11b) = ([1, 0], [2, 1]);
     assert_eq!(
-        try_get_quadro_node_connection(&coord11a, &coord11b, &map11, &map_size),
+        try_get_quadro_node_connection(&coord11a, &coord11b, &board11, PathSelection::First),
         None
     );
     assert_eq!(
-        try_get_quadro_node_connection(&coord11b, &coord11a, &map11, &map_size),
+        try_get_quadro_node_connection(&coord11b, &coord11a, &board11, PathSelection::First),
         None
     );
 
     let map12 = HashMap::from([([1, 1], 1), ([1, 2], 0), ([2, 1], 0), ([2, 2], 1)]);
+    let board12 = Board::from_map(&map12, &map_size);
     let (coord12a, coord12b) = ([1, 2], [2, 1]);
     assert_eq!(
-        try_get_quadro_node_connection(&coord12a, &coord12b, &map12, &map_size),
+        try_get_quadro_node_connection(&coord12a, &coord12b, &board12, PathSelection::First),
         None
     );
     assert_eq!(
-        try_get_quadro_node_connection(&coord12b, &coord12a, &map12, &map_size),
+        try_get_quadro_node_connection(&coord12b, &coord12a, &board12, PathSelection::First),
         None
     );
 }
 
 #[test]
 fn test_try_get_node_connection() {
+    use super::super::components::TileMap;
     use std::collections::HashMap;
     /*
      * x x x x
@@ -840,6 +982,7 @@ fn test_try_get_node_connection() {
      */
     let map: TileMap = HashMap::from([([1, 1], 0), ([1, 2], 0), ([2, 1], 1), ([3, 3], 1)]);
     let map_size = [4, 4];
+    let board = Board::from_map(&map, &map_size);
 
     let reverse = |nodes: Option<Nodes>| match nodes {
         Some(arr) => {
@@ -853,44 +996,92 @@ fn test_try_get_node_connection() {
     let (coord1a, coord1b) = ([1, 1], [1, 2]);
     let expected1 = Some(vec![coord1a, coord1b]);
     assert_eq!(
-        try_get_node_connection(&coord1a, &coord1b, &map, &map_size),
+        try_get_node_connection(
+            &coord1a,
+            &coord1b,
+            &board,
+            &RuleSet::default(),
+            PathSelection::First
+        ),
         expected1
     );
     assert_eq!(
-        try_get_node_connection(&coord1b, &coord1a, &map, &map_size),
+        try_get_node_connection(
+            &coord1b,
+            &coord1a,
+            &board,
+            &RuleSet::default(),
+            PathSelection::First
+        ),
         reverse(expected1)
     );
 
     let (coord2a, coord2b) = ([0, 0], [1, 0]);
     let expected2 = Some(vec![coord2a, coord2b]);
     assert_eq!(
-        try_get_node_connection(&coord2a, &coord2b, &map, &map_size),
+        try_get_node_connection(
+            &coord2a,
+            &coord2b,
+            &board,
+            &RuleSet::default(),
+            PathSelection::First
+        ),
         expected2
     );
     assert_eq!(
-        try_get_node_connection(&coord2b, &coord2a, &map, &map_size),
+        try_get_node_connection(
+            &coord2b,
+            &coord2a,
+            &board,
+            &RuleSet::default(),
+            PathSelection::First
+        ),
         reverse(expected2)
     );
 
     let (coord3a, coord3b) = ([0, 0], [0, 3]);
     let expected3 = Some(vec![coord3a, coord3b]);
     assert_eq!(
-        try_get_node_connection(&coord3a, &coord3b, &map, &map_size),
+        try_get_node_connection(
+            &coord3a,
+            &coord3b,
+            &board,
+            &RuleSet::default(),
+            PathSelection::First
+        ),
         expected3
     );
     assert_eq!(
-        try_get_node_connection(&coord3b, &coord3a, &map, &map_size),
+        try_get_node_connection(
+            &coord3b,
+            &coord3a,
+            &board,
+            &RuleSet::default(),
+            PathSelection::First
+        ),
         reverse(expected3)
     );
 
     let (coord4a, coord4b) = ([0, 0], [3, 0]);
     let expected4 = Some(vec![coord4a, coord4b]);
     assert_eq!(
-        try_get_node_connection(&coord4a, &coord4b, &map, &map_size),
+        try_get_node_connection(
+            &coord4a,
+            &coord4b,
+            &board,
+            &RuleSet::default(),
+            PathSelection::First
+        ),
         expected4
     );
     assert_eq!(
-        try_get_node_connection(&coord4b, &coord4a, &map, &map_size),
+        try_get_node_connection(
+            &coord4b,
+            &coord4a,
+            &board,
+            &RuleSet::default(),
+            PathSelection::First
+        ),
         reverse(expected4)
     );
 
@@ -898,88 +1089,496 @@ fn test_try_get_node_connection() {
     let expected5a = Some(vec![coord5a, [3, 1], coord5b]);
     let expected5b = Some(vec![coord5b, [2, 3], coord5a]);
     assert_eq!(
-        try_get_node_connection(&coord5a, &coord5b, &map, &map_size),
+        try_get_node_connection(
+            &coord5a,
+            &coord5b,
+            &board,
+            &RuleSet::default(),
+            PathSelection::First
+        ),
         expected5a
     );
     assert_eq!(
-        try_get_node_connection(&coord5b, &coord5a, &map, &map_size),
+        try_get_node_connection(
+            &coord5b,
+            &coord5a,
+            &board,
+            &RuleSet::default(),
+            PathSelection::First
+        ),
         expected5b
     );
 
     let (coord6a, coord6b) = ([0, 3], [3, 0]);
     let expected6 = Some(vec![coord6a, [0, 0], coord6b]);
     assert_eq!(
-        try_get_node_connection(&coord6a, &coord6b, &map, &map_size),
+        try_get_node_connection(
+            &coord6a,
+            &coord6b,
+            &board,
+            &RuleSet::default(),
+            PathSelection::First
+        ),
         expected6
     );
     assert_eq!(
-        try_get_node_connection(&coord6b, &coord6a, &map, &map_size),
+        try_get_node_connection(
+            &coord6b,
+            &coord6a,
+            &board,
+            &RuleSet::default(),
+            PathSelection::First
+        ),
         reverse(expected6)
     );
 
     let (coord7a, coord7b) = ([2, 3], [3, 1]);
     let expected7 = Some(vec![coord7a, [2, 2], [3, 2], coord7b]);
     assert_eq!(
-        try_get_node_connection(&coord7a, &coord7b, &map, &map_size),
+        try_get_node_connection(
+            &coord7a,
+            &coord7b,
+            &board,
+            &RuleSet::default(),
+            PathSelection::First
+        ),
         expected7
     );
     assert_eq!(
-        try_get_node_connection(&coord7b, &coord7a, &map, &map_size),
+        try_get_node_connection(
+            &coord7b,
+            &coord7a,
+            &board,
+            &RuleSet::default(),
+            PathSelection::First
+        ),
         reverse(expected7)
     );
 
     let (coord8a, coord8b) = ([1, 0], [1, 3]);
     let expected8 = Some(vec![coord8a, [0, 0], [0, 3], coord8b]);
     assert_eq!(
-        try_get_node_connection(&coord8a, &coord8b, &map, &map_size),
+        try_get_node_connection(
+            &coord8a,
+            &coord8b,
+            &board,
+            &RuleSet::default(),
+            PathSelection::First
+        ),
         expected8
     );
     assert_eq!(
-        try_get_node_connection(&coord8b, &coord8a, &map, &map_size),
+        try_get_node_connection(
+            &coord8b,
+            &coord8a,
+            &board,
+            &RuleSet::default(),
+            PathSelection::First
+        ),
         reverse(expected8)
     );
 
     let (coord9a, coord9b) = ([1, 0], [2, 2]);
     let expected9 = Some(vec![coord9a, [3, 0], [3, 2], coord9b]);
     assert_eq!(
-        try_get_node_connection(&coord9a, &coord9b, &map, &map_size),
+        try_get_node_connection(
+            &coord9a,
+            &coord9b,
+            &board,
+            &RuleSet::default(),
+            PathSelection::First
+        ),
         expected9
     );
     assert_eq!(
-        try_get_node_connection(&coord9b, &coord9a, &map, &map_size),
+        try_get_node_connection(
+            &coord9b,
+            &coord9a,
+            &board,
+            &RuleSet::default(),
+            PathSelection::First
+        ),
         reverse(expected9)
     );
 
     let (coord10a, coord10b) = ([0, 1], [3, 2]);
     let expected10 = Some(vec![coord10a, [0, 0], [3, 0], coord10b]);
     assert_eq!(
-        try_get_node_connection(&coord10a, &coord10b, &map, &map_size),
+        try_get_node_connection(
+            &coord10a,
+            &coord10b,
+            &board,
+            &RuleSet::default(),
+            PathSelection::First
+        ),
         expected10
     );
     assert_eq!(
-        try_get_node_connection(&coord10b, &coord10a, &map, &map_size),
+        try_get_node_connection(
+            &coord10b,
+            &coord10a,
+            &board,
+            &RuleSet::default(),
+            PathSelection::First
+        ),
         reverse(expected10)
     );
 
     let (coord11a, coord11b) = ([0, 1], [2, 2]);
     let expected11 = Some(vec![coord11a, [0, 3], [2, 3], coord11b]);
     assert_eq!(
-        try_get_node_connection(&coord11a, &coord11b, &map, &map_size),
+        try_get_node_connection(
+            &coord11a,
+            &coord11b,
+            &board,
+            &RuleSet::default(),
+            PathSelection::First
+        ),
         expected11
     );
     assert_eq!(
-        try_get_node_connection(&coord11b, &coord11a, &map, &map_size),
+        try_get_node_connection(
+            &coord11b,
+            &coord11a,
+            &board,
+            &RuleSet::default(),
+            PathSelection::First
+        ),
         reverse(expected11)
     );
 
     let (coord12a, coord12b) = ([1, 3], [3, 2]);
     let expected12 = Some(vec![coord12a, [2, 3], [2, 2], coord12b]);
     assert_eq!(
-        try_get_node_connection(&coord12a, &coord12b, &map, &map_size),
+        try_get_node_connection(
+            &coord12a,
+            &coord12b,
+            &board,
+            &RuleSet::default(),
+            PathSelection::First
+        ),
         expected12
     );
     assert_eq!(
-        try_get_node_connection(&coord12b, &coord12a, &map, &map_size),
+        try_get_node_connection(
+            &coord12b,
+            &coord12a,
+            &board,
+            &RuleSet::default(),
+            PathSelection::First
+        ),
         reverse(expected12)
     );
 }
+
+#[test]
+fn test_try_get_node_connection_shortest() {
+    use super::super::components::TileMap;
+    use std::collections::HashMap;
+    /* tile map:
+     * x x x x x
+     * x x x x x
+     * x 0 1 0 x
+     * x x x x x
+     */
+    let map: TileMap = HashMap::from([([1, 2], 0), ([2, 2], 1), ([3, 2], 0)]);
+    let board = Board::from_map(&map, &[5, 4]);
+
+    assert_eq!(
+        try_get_node_connection(
+            &[1, 2],
+            &[3, 2],
+            &board,
+            &RuleSet::default(),
+            PathSelection::First
+        ),
+        Some(vec![[1, 2], [1, 0], [3, 0], [3, 2]])
+    );
+    assert_eq!(
+        try_get_node_connection(
+            &[1, 2],
+            &[3, 2],
+            &board,
+            &RuleSet::default(),
+            PathSelection::Shortest
+        ),
+        Some(vec![[1, 2], [1, 1], [3, 1], [3, 2]])
+    );
+    assert_eq!(
+        try_get_node_connection(
+            &[3, 2],
+            &[1, 2],
+            &board,
+            &RuleSet::default(),
+            PathSelection::Shortest
+        ),
+        Some(vec![[3, 2], [3, 1], [1, 1], [1, 2]])
+    );
+
+    /* tile map:
+     * x x x x x
+     * x x x 0 x
+     * x x 1 1 x
+     * x x x 0 x
+     */
+    let map: TileMap = HashMap::from([([3, 1], 0), ([2, 2], 1), ([3, 2], 1), ([3, 3], 0)]);
+    let board = Board::from_map(&map, &[5, 4]);
+
+    assert_eq!(
+        try_get_node_connection(
+            &[3, 1],
+            &[3, 3],
+            &board,
+            &RuleSet::default(),
+            PathSelection::First
+        ),
+        Some(vec![[3, 1], [0, 1], [0, 3], [3, 3]])
+    );
+    assert_eq!(
+        try_get_node_connection(
+            &[3, 1],
+            &[3, 3],
+            &board,
+            &RuleSet::default(),
+            PathSelection::Shortest
+        ),
+        Some(vec![[3, 1], [4, 1], [4, 3], [3, 3]])
+    );
+}
+
+#[test]
+fn test_get_all_node_connections() {
+    use super::super::components::TileMap;
+    use std::collections::HashMap;
+    /* tile map:
+     * x x x x
+     * x 0 1 x
+     * x x 0 x
+     */
+    let map: TileMap = HashMap::from([([1, 1], 0), ([2, 1], 1), ([2, 2], 0)]);
+    let board = Board::from_map(&map, &[4, 3]);
+
+    let actual: Vec<(Nodes, usize, usize)> =
+        get_all_node_connections(&[1, 1], &[2, 2], &board, &RuleSet::default())
+            .into_iter()
+            .map(|conn| (conn.nodes, conn.turns, conn.length))
+            .collect();
+    assert_eq!(
+        actual,
+        vec![
+            (vec![[1, 1], [1, 2], [2, 2]], 1, 2),
+            (vec![[1, 1], [0, 1], [0, 2], [2, 2]], 2, 4),
+        ]
+    );
+
+    let turns = |rules: &RuleSet| -> Vec<usize> {
+        get_all_node_connections(&[1, 1], &[2, 2], &board, rules)
+            .into_iter()
+            .map(|conn| conn.turns)
+            .collect()
+    };
+    assert_eq!(
+        turns(&RuleSet {
+            max_turns: 1,
+            ..RuleSet::default()
+        }),
+        vec![1]
+    );
+    assert_eq!(
+        turns(&RuleSet {
+            max_turns: 0,
+            ..RuleSet::default()
+        }),
+        Vec::<usize>::new()
+    );
+
+    /* Without the margin, a tile on the edge still connects within the board. */
+    let inside = RuleSet {
+        allow_outside: false,
+        ..RuleSet::default()
+    };
+    let actual: Vec<Nodes> = get_all_node_connections(
+        &[1, 1],
+        &[2, 2],
+        &inside.create_board(&map, &[3, 3]),
+        &inside,
+    )
+    .into_iter()
+    .map(|conn| conn.nodes)
+    .collect();
+    assert_eq!(
+        actual,
+        vec![
+            vec![[1, 1], [1, 2], [2, 2]],
+            vec![[1, 1], [0, 1], [0, 2], [2, 2]],
+        ]
+    );
+
+    assert_eq!(
+        get_all_node_connections(&[1, 1], &[1, 1], &board, &RuleSet::default()),
+        Vec::new()
+    );
+}
+
+#[test]
+fn test_get_all_node_connections_cross_check() {
+    use super::super::components::TileMap;
+    use super::utils::splitmix64;
+    let size = [6, 5];
+    let rule_sets = [
+        RuleSet::default(),
+        RuleSet {
+            allow_outside: false,
+            ..RuleSet::default()
+        },
+        RuleSet {
+            max_turns: 1,
+            allow_outside: false,
+            ..RuleSet::default()
+        },
+    ];
+    let mut seed = 0u64;
+    for (n, rules) in (0..300).zip(rule_sets.iter().cycle()) {
+        let mut map = TileMap::new();
+        for y in 0..size[1] {
+            for x in 0..size[0] {
+                seed = splitmix64(seed);
+                if seed.is_multiple_of(3) {
+                    map.insert([x, y], 0);
+                }
+            }
+        }
+        /* The first boards are laid out as given, without a margin. */
+        let board = match n < 100 {
+            true => Board::from_map(&map, &size),
+            false => rules.create_board(&map, &size),
+        };
+
+        let coords: Vec<Coord> = board.iter().map(|(coord, _)| coord).collect();
+        for (i, coord1) in coords.iter().enumerate() {
+            for coord2 in coords[(i + 1)..].iter() {
+                let all = get_all_node_connections(coord1, coord2, &board, rules);
+                let first =
+                    try_get_node_connection(coord1, coord2, &board, rules, PathSelection::First);
+                let shortest =
+                    try_get_node_connection(coord1, coord2, &board, rules, PathSelection::Shortest);
+
+                assert_eq!(all.is_empty(), first.is_none());
+                if let (Some(first), Some(shortest), Some(best)) = (first, shortest, all.first()) {
+                    assert!(all.iter().any(|conn| conn.nodes == first));
+                    assert_eq!(first.len() - 2, best.turns);
+                    assert_eq!(shortest.len() - 2, best.turns);
+                    assert_eq!(get_node_length(&shortest), best.length);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_try_get_node_connection_rules() {
+    use super::super::components::TileMap;
+    use std::collections::HashMap;
+    /* tile map:
+     * x x x x x
+     * x 0 1 x x
+     * 1 1 1 x x
+     * x 0 x x x
+     * x x x x x
+     */
+    let map: TileMap = HashMap::from([
+        ([1, 1], 0),
+        ([2, 1], 1),
+        ([0, 2], 1),
+        ([1, 2], 1),
+        ([2, 2], 1),
+        ([1, 3], 0),
+    ]);
+    let board = Board::from_map(&map, &[5, 5]);
+    let rules = |max_turns| RuleSet {
+        max_turns,
+        ..RuleSet::default()
+    };
+
+    for max_turns in 0..=2 {
+        assert_eq!(
+            try_get_node_connection(
+                &[1, 1],
+                &[1, 3],
+                &board,
+                &rules(max_turns),
+                PathSelection::First
+            ),
+            None
+        );
+    }
+    assert_eq!(
+        try_get_node_connection(&[1, 1], &[1, 3], &board, &rules(3), PathSelection::First),
+        Some(vec![[1, 1], [1, 0], [3, 0], [3, 3], [1, 3]])
+    );
+    assert_eq!(
+        try_get_node_connection(&[1, 3], &[1, 1], &board, &rules(4), PathSelection::First),
+        Some(vec![[1, 3], [3, 3], [3, 0], [1, 0], [1, 1]])
+    );
+
+    assert_eq!(
+        try_get_node_connection(&[0, 2], &[2, 2], &board, &rules(0), PathSelection::First),
+        None
+    );
+    assert_eq!(
+        try_get_node_connection(&[2, 1], &[0, 2], &board, &rules(1), PathSelection::First),
+        None
+    );
+    assert_eq!(
+        try_get_node_connection(&[2, 1], &[0, 2], &board, &rules(3), PathSelection::First),
+        Some(vec![[2, 1], [2, 0], [0, 0], [0, 2]])
+    );
+}
+
+#[test]
+fn test_try_get_node_connection_walls() {
+    use super::super::components::{TileMap, WALL};
+    use std::collections::HashMap;
+    /* tile map:
+     * x x x x x
+     * x 0 # 0 x
+     * x x x x x
+     */
+    let mut map: TileMap = HashMap::from([([1, 1], 0), ([2, 1], WALL), ([3, 1], 0)]);
+    let board = Board::from_map(&map, &[5, 3]);
+
+    assert_eq!(get_grid(&[1, 1], &RIGHT, &board), None);
+    assert!(!can_horizontal_connect(1, 3, 1, true, &board));
+    assert_eq!(
+        try_get_node_connection(
+            &[1, 1],
+            &[3, 1],
+            &board,
+            &RuleSet::default(),
+            PathSelection::First
+        ),
+        Some(vec![[1, 1], [1, 0], [3, 0], [3, 1]])
+    );
+
+    /* tile map:
+     * # # # # #
+     * x 0 # 0 x
+     * # # # # #
+     */
+    for x in 0..5 {
+        map.insert([x, 0], WALL);
+        map.insert([x, 2], WALL);
+    }
+    let board = Board::from_map(&map, &[5, 3]);
+    for max_turns in [2, 4] {
+        let rules = RuleSet {
+            max_turns,
+            ..RuleSet::default()
+        };
+        assert_eq!(
+            try_get_node_connection(&[1, 1], &[3, 1], &board, &rules, PathSelection::First),
+            None
+        );
+    }
+    assert!(get_all_node_connections(&[1, 1], &[3, 1], &board, &RuleSet::default()).is_empty());
+}
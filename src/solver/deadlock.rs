@@ -0,0 +1,194 @@
+use std::cmp::{max, min};
+
+use super::super::components::{Coord, Tile, TileMap, WALL};
+use super::board::Board;
+use super::connect::{try_get_node_connection, PathSelection};
+use super::lut::{board_to_coord_collection, create_coord_pair_collection, CoordCollection};
+use super::rules::RuleSet;
+
+fn get_bounding_box(coords: &[Coord]) -> [Coord; 2] {
+    coords.iter().fold(
+        [[usize::MAX, usize::MAX], [0, 0]],
+        |[[xmin, ymin], [xmax, ymax]], &[x, y]| {
+            [[min(x, xmin), min(y, ymin)], [max(x, xmax), max(y, ymax)]]
+        },
+    )
+}
+
+fn is_box_overlapped(
+    &[[xmin1, ymin1], [xmax1, ymax1]]: &[Coord; 2],
+    &[[xmin2, ymin2], [xmax2, ymax2]]: &[Coord; 2],
+) -> bool {
+    max(xmin1, xmin2) <= min(xmax1, xmax2) && max(ymin1, ymin2) <= min(ymax1, ymax2)
+}
+
+fn get_candidate_sets(lut: &CoordCollection) -> Vec<Vec<Tile>> {
+    /*
+     * Candidates are every single tile kind, and every two kinds with
+     * exactly two copies left whose bounding boxes overlap, e.g.
+     *
+     * x 0 x    0 1
+     * 1 x 1    1 0
+     * x 0 x
+     */
+    let mut tiles: Vec<Tile> = lut
+        .iter()
+        .filter(|(_, coords)| coords.len() >= 2)
+        .map(|(&tile, _)| tile)
+        .collect();
+    tiles.sort();
+
+    let mut sets: Vec<Vec<Tile>> = tiles.iter().map(|&tile| vec![tile]).collect();
+
+    let pairs: Vec<(Tile, [Coord; 2])> = tiles
+        .iter()
+        .filter(|tile| lut[tile].len() == 2)
+        .map(|&tile| (tile, get_bounding_box(&lut[&tile])))
+        .collect();
+    for (i, (tile1, box1)) in pairs.iter().enumerate() {
+        for (tile2, box2) in pairs[(i + 1)..].iter() {
+            if is_box_overlapped(box1, box2) {
+                sets.push(vec![*tile1, *tile2]);
+            }
+        }
+    }
+    sets
+}
+
+fn can_remove_any(
+    tiles: &[Tile],
+    lut: &CoordCollection,
+    walls: &[Coord],
+    size: &[usize; 2],
+    rules: &RuleSet,
+) -> bool {
+    /*
+     * The first pair removed among `tiles` sees all of their copies still
+     * on the board, while any other tile may already be gone. So if no pair
+     * connects on a board holding only these copies and the walls, none
+     * ever will.
+     */
+    let sub_lut: CoordCollection = tiles
+        .iter()
+        .map(|tile| (*tile, lut[tile].clone()))
+        .collect();
+    let mut board = Board::new(size);
+    walls.iter().for_each(|coord| {
+        board.insert(coord, WALL);
+    });
+    for (&tile, coords) in sub_lut.iter() {
+        coords.iter().for_each(|coord| {
+            board.insert(coord, tile);
+        });
+    }
+
+    create_coord_pair_collection(&sub_lut)
+        .values()
+        .flatten()
+        .any(|[coord1, coord2]| {
+            try_get_node_connection(coord1, coord2, &board, rules, PathSelection::First).is_some()
+        })
+}
+
+pub(super) fn find_deadlock(board: &Board, rules: &RuleSet) -> Option<Vec<Tile>> {
+    /*
+     * Tiles move after each removal in gravity mode, so a blocked pattern
+     * may still be broken up later.
+     */
+    if rules.gravity {
+        return None;
+    }
+
+    let lut = board_to_coord_collection(board, &rules.classes);
+    let walls: Vec<Coord> = board.walls().collect();
+    get_candidate_sets(&lut)
+        .into_iter()
+        .find(|tiles| !can_remove_any(tiles, &lut, &walls, &board.size(), rules))
+}
+
+pub fn detect_deadlock(map: &TileMap, size: &[usize; 2], rules: &RuleSet) -> Option<Vec<Tile>> {
+    find_deadlock(&rules.create_board(map, size), rules)
+}
+
+#[test]
+fn test_get_bounding_box() {
+    assert_eq!(get_bounding_box(&[[2, 1], [0, 3]]), [[0, 1], [2, 3]]);
+    assert_eq!(get_bounding_box(&[[1, 1]]), [[1, 1], [1, 1]]);
+}
+
+#[test]
+fn test_get_candidate_sets() {
+    use std::collections::HashMap;
+    /*
+     * 0 1 2 3
+     * 1 0 2 x
+     * x x 3 4
+     */
+    let lut: CoordCollection = HashMap::from([
+        (0, vec![[0, 0], [1, 1]]),
+        (1, vec![[1, 0], [0, 1]]),
+        (2, vec![[2, 0], [2, 1]]),
+        (3, vec![[3, 0], [2, 2]]),
+        (4, vec![[3, 2]]),
+    ]);
+    assert_eq!(
+        get_candidate_sets(&lut),
+        vec![vec![0], vec![1], vec![2], vec![3], vec![0, 1], vec![2, 3]]
+    );
+}
+
+#[test]
+fn test_detect_deadlock() {
+    use std::collections::HashMap;
+    /*
+     * 0 1 2 2
+     * 1 0 2 2
+     */
+    let map: TileMap = HashMap::from([
+        ([0, 0], 0),
+        ([0, 1], 1),
+        ([1, 0], 1),
+        ([1, 1], 0),
+        ([2, 0], 2),
+        ([2, 1], 2),
+        ([3, 0], 2),
+        ([3, 1], 2),
+    ]);
+    assert_eq!(
+        detect_deadlock(&map, &[4, 2], &RuleSet::default()),
+        Some(vec![0, 1])
+    );
+
+    /*
+     * 0 1 1 2
+     * 0 2 x x
+     */
+    let map: TileMap = HashMap::from([
+        ([0, 0], 0),
+        ([0, 1], 0),
+        ([1, 0], 1),
+        ([1, 1], 2),
+        ([2, 0], 1),
+        ([3, 0], 2),
+    ]);
+    assert_eq!(detect_deadlock(&map, &[4, 2], &RuleSet::default()), None);
+    assert_eq!(
+        detect_deadlock(&HashMap::new(), &[0, 0], &RuleSet::default()),
+        None
+    );
+
+    /*
+     * # # # # #
+     * # 3 # 3 #
+     * # # # # #
+     */
+    let map: TileMap = HashMap::from_iter(
+        (0..5)
+            .flat_map(|x| (0..3).map(move |y| ([x, y], WALL)))
+            .chain([([1, 1], 3), ([3, 1], 3)]),
+    );
+    assert_eq!(
+        detect_deadlock(&map, &[5, 3], &RuleSet::default()),
+        Some(vec![3])
+    );
+}
@@ -0,0 +1,98 @@
+use super::super::components::TileMap;
+use super::rules::RuleSet;
+use super::solver::{SichuanSolver, SolverError, SolverMetrics};
+
+const CHOICE_WEIGHT: f64 = 40.0;
+const BACKTRACK_WEIGHT: f64 = 10.0;
+const OPTION_WEIGHT: f64 = 5.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Difficulty {
+    pub metrics: SolverMetrics,
+    pub forced_ratio: f64,
+    pub mean_options: f64,
+    pub score: f64,
+}
+
+impl Difficulty {
+    fn from_metrics(metrics: SolverMetrics) -> Self {
+        /*
+         * score = 40 * (share of moves which needed a choice)
+         *       + 10 * log2(1 + backtracks)
+         *       +  5 * (options per choice - 1)
+         *
+         * A board solved by forced moves alone scores 0, and the score has
+         * no upper bound.
+         */
+        let forced_ratio = match metrics.n_moves {
+            0 => 1.0,
+            n_moves => metrics.n_forced_moves as f64 / n_moves as f64,
+        };
+        let mean_options = match metrics.n_snapshots {
+            0 => 1.0,
+            n_snapshots => metrics.n_options as f64 / n_snapshots as f64,
+        };
+        let score = CHOICE_WEIGHT * (1.0 - forced_ratio)
+            + BACKTRACK_WEIGHT * (1.0 + metrics.n_backtracks as f64).log2()
+            + OPTION_WEIGHT * (mean_options - 1.0);
+        Self {
+            metrics,
+            forced_ratio,
+            mean_options,
+            score,
+        }
+    }
+}
+
+pub fn rate_difficulty(
+    map: &TileMap,
+    size: &[usize; 2],
+    rules: &RuleSet,
+    max_steps: Option<usize>,
+) -> Result<Difficulty, SolverError> {
+    let mut solver = SichuanSolver::new(map, size, rules);
+    solver.set_max_steps(max_steps);
+    solver.solve()?;
+    Ok(Difficulty::from_metrics(solver.metrics()))
+}
+
+#[test]
+fn test_rate_difficulty() {
+    use std::collections::HashMap;
+    /*
+     * 0 0 1 1
+     */
+    let map: TileMap = HashMap::from([([0, 0], 0), ([1, 0], 0), ([2, 0], 1), ([3, 0], 1)]);
+    let easy = rate_difficulty(&map, &[4, 1], &RuleSet::default(), None).unwrap();
+    assert_eq!(easy.metrics.n_moves, 2);
+    assert_eq!(easy.metrics.n_forced_moves, 2);
+    assert_eq!(easy.metrics.n_snapshots, 0);
+    assert_eq!(easy.score, 0.0);
+
+    /*
+     * 0 1 0 1
+     * 1 0 1 0
+     */
+    let map: TileMap = HashMap::from([
+        ([0, 0], 0),
+        ([1, 0], 1),
+        ([2, 0], 0),
+        ([3, 0], 1),
+        ([0, 1], 1),
+        ([1, 1], 0),
+        ([2, 1], 1),
+        ([3, 1], 0),
+    ]);
+    let hard = rate_difficulty(&map, &[4, 2], &RuleSet::default(), None).unwrap();
+    assert_eq!(hard.metrics.n_moves, 4);
+    assert!(hard.metrics.n_branch_points > 0);
+    assert!(hard.forced_ratio < 1.0);
+    assert!(hard.mean_options > 1.0);
+    assert!(hard.score > easy.score);
+
+    let map: TileMap = HashMap::from([([0, 0], 0), ([1, 0], 1)]);
+    assert!(matches!(
+        rate_difficulty(&map, &[2, 1], &RuleSet::default(), None),
+        Err(SolverError::OddTileCount(_))
+    ));
+}
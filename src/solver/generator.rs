@@ -0,0 +1,264 @@
+use std::collections::VecDeque;
+
+use super::super::components::{Coord, Tile, TileMap};
+use super::board::Board;
+use super::connect::{try_get_node_connection, PathSelection};
+use super::rules::RuleSet;
+use super::solver::SichuanSolver;
+use super::utils::SeededRng;
+
+const MAX_ATTEMPTS: usize = 100;
+const GRAVITY_CHECK_STEPS: usize = 10_000;
+
+struct DominoMatching {
+    cols: usize,
+    rows: usize,
+    partners: Vec<Option<usize>>,
+}
+
+impl DominoMatching {
+    fn new(rows: usize, cols: usize) -> Self {
+        /*
+         * Cells are paired with a neighbour along whichever side has an even
+         * length, which is a perfect matching of the empty grid.
+         */
+        let partners = (0..rows * cols)
+            .map(|i| {
+                let [x, y] = [i % cols, i / cols];
+                match cols.is_multiple_of(2) {
+                    true => Some(y * cols + (x ^ 1)),
+                    false => Some((y ^ 1) * cols + x),
+                }
+            })
+            .collect();
+        Self {
+            cols,
+            rows,
+            partners,
+        }
+    }
+
+    fn index(&self, &[x, y]: &Coord) -> usize {
+        y * self.cols + x
+    }
+
+    fn coord(&self, i: usize) -> Coord {
+        [i % self.cols, i / self.cols]
+    }
+
+    fn neighbours(&self, i: usize) -> Vec<usize> {
+        let [x, y] = self.coord(i);
+        let mut neighbours = Vec::new();
+        if x > 0 {
+            neighbours.push(i - 1);
+        }
+        if x + 1 < self.cols {
+            neighbours.push(i + 1);
+        }
+        if y > 0 {
+            neighbours.push(i - self.cols);
+        }
+        if y + 1 < self.rows {
+            neighbours.push(i + self.cols);
+        }
+        neighbours
+    }
+
+    fn find_alternating_path(
+        &self,
+        from: usize,
+        to: usize,
+        excluded: [usize; 2],
+    ) -> Option<Vec<usize>> {
+        /*
+         * Breadth-first search from `from` to `to`, stepping to a free
+         * neighbour and then on to that neighbour's partner.
+         */
+        let mut parents: Vec<Option<usize>> = vec![None; self.partners.len()];
+        let mut queue = VecDeque::from([from]);
+        parents[from] = Some(from);
+        while let Some(i) = queue.pop_front() {
+            for n in self.neighbours(i) {
+                if excluded.contains(&n) || self.partners[n].is_none() || parents[n].is_some() {
+                    continue;
+                }
+                parents[n] = Some(i);
+                if n == to {
+                    let mut path = vec![n];
+                    let mut cursor = n;
+                    while cursor != from {
+                        cursor = parents[cursor].unwrap();
+                        path.push(cursor);
+                    }
+                    return Some(path);
+                }
+                if let Some(partner) = self.partners[n] {
+                    if parents[partner].is_none() && !excluded.contains(&partner) {
+                        parents[partner] = Some(n);
+                        queue.push_back(partner);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn try_remove(&mut self, coord1: &Coord, coord2: &Coord) -> bool {
+        /*
+         * Taking out two cells keeps a perfect matching of the rest if the
+         * partners they leave behind can be joined by an alternating path.
+         */
+        let (i1, i2) = (self.index(coord1), self.index(coord2));
+        let (p1, p2) = match (self.partners[i1], self.partners[i2]) {
+            (Some(p1), Some(p2)) => (p1, p2),
+            _ => return false,
+        };
+        if p1 != i2 {
+            let path = match self.find_alternating_path(p1, p2, [i1, i2]) {
+                Some(path) => path,
+                None => return false,
+            };
+            for pair in path.chunks(2) {
+                self.partners[pair[0]] = Some(pair[1]);
+                self.partners[pair[1]] = Some(pair[0]);
+            }
+        }
+        self.partners[i1] = None;
+        self.partners[i2] = None;
+        true
+    }
+}
+
+fn place_pair(
+    empty: &[Coord],
+    tile: Tile,
+    board: &mut Board,
+    matching: &mut DominoMatching,
+    rules: &RuleSet,
+) -> Option<[usize; 2]> {
+    /*
+     * The pair placed last is the first one to be removed, so each pair only
+     * has to connect across the pairs placed before it. The cells still empty
+     * are kept coverable by dominoes, so at worst two neighbours are left to
+     * be placed next and the construction never gets stuck.
+     */
+    let padding = rules.padding();
+    for (i, coord1) in empty.iter().enumerate() {
+        for (j, coord2) in empty.iter().enumerate().skip(i + 1) {
+            let padded1 = [coord1[0] + padding, coord1[1] + padding];
+            let padded2 = [coord2[0] + padding, coord2[1] + padding];
+            board.insert(&padded1, tile);
+            board.insert(&padded2, tile);
+            if try_get_node_connection(&padded1, &padded2, board, rules, PathSelection::First)
+                .is_some()
+                && matching.try_remove(coord1, coord2)
+            {
+                return Some([i, j]);
+            }
+            board.remove(&padded1);
+            board.remove(&padded2);
+        }
+    }
+    None
+}
+
+fn try_generate_board(
+    rows: usize,
+    cols: usize,
+    tiles: &[Tile],
+    rules: &RuleSet,
+    rng: &mut SeededRng,
+) -> Option<TileMap> {
+    let padding = rules.padding();
+    let mut board = Board::new(&[cols + padding * 2, rows + padding * 2]);
+    let mut matching = DominoMatching::new(rows, cols);
+    let mut empty: Vec<Coord> = (0..rows)
+        .flat_map(|y| (0..cols).map(move |x| [x, y]))
+        .collect();
+    while !empty.is_empty() {
+        rng.shuffle(&mut empty);
+        let tile = tiles[rng.below(tiles.len())];
+        let [i, j] = place_pair(&empty, tile, &mut board, &mut matching, rules)?;
+        empty.swap_remove(j);
+        empty.swap_remove(i);
+    }
+
+    let map: TileMap = board
+        .iter()
+        .map(|([x, y], tile)| ([x - padding, y - padding], tile))
+        .collect();
+
+    /*
+     * Tiles move when they fall, so placing pairs in reverse does not prove
+     * a gravity board solvable. Such boards are checked by the solver.
+     */
+    if rules.gravity {
+        let mut solver = SichuanSolver::new(&map, &[cols, rows], rules);
+        solver.set_max_steps(Some(GRAVITY_CHECK_STEPS));
+        solver.solve().ok()?;
+    }
+    Some(map)
+}
+
+pub fn generate_board(
+    rows: usize,
+    cols: usize,
+    tiles: &[Tile],
+    rules: &RuleSet,
+    seed: u64,
+) -> Option<TileMap> {
+    if tiles.is_empty() || !(rows * cols).is_multiple_of(2) {
+        return None;
+    }
+
+    let mut rng = SeededRng::new(seed);
+    (0..MAX_ATTEMPTS).find_map(|_| try_generate_board(rows, cols, tiles, rules, &mut rng))
+}
+
+#[test]
+fn test_domino_matching() {
+    /*
+     * a b c d
+     * e f g h
+     */
+    let mut matching = DominoMatching::new(2, 4);
+    assert!(!matching.try_remove(&[0, 0], &[2, 0]));
+    assert!(matching.try_remove(&[1, 0], &[2, 0]));
+    assert!(matching.try_remove(&[0, 0], &[3, 0]));
+    assert!(!matching.try_remove(&[0, 0], &[0, 1]));
+    assert!(matching.try_remove(&[0, 1], &[3, 1]));
+    assert!(matching.try_remove(&[1, 1], &[2, 1]));
+    assert!(matching.partners.iter().all(|partner| partner.is_none()));
+}
+
+#[test]
+fn test_generate_board() {
+    let tiles: Vec<Tile> = (0..6).collect();
+    let inside = RuleSet {
+        allow_outside: false,
+        ..RuleSet::default()
+    };
+
+    for rules in [RuleSet::default(), inside] {
+        for seed in 0..5 {
+            let map = generate_board(4, 6, &tiles, &rules, seed).unwrap();
+            assert_eq!(map.len(), 24);
+            assert!(map.keys().all(|&[x, y]| x < 6 && y < 4));
+            assert!(map.values().all(|tile| tiles.contains(tile)));
+            assert_eq!(
+                generate_board(4, 6, &tiles, &rules, seed),
+                Some(map.clone())
+            );
+
+            let mut solver = SichuanSolver::new(&map, &[6, 4], &rules);
+            assert_eq!(solver.solve(), Ok(()));
+        }
+    }
+    assert_ne!(
+        generate_board(4, 6, &tiles, &RuleSet::default(), 0),
+        generate_board(4, 6, &tiles, &RuleSet::default(), 1)
+    );
+
+    assert_eq!(generate_board(3, 3, &tiles, &RuleSet::default(), 0), None);
+    assert_eq!(generate_board(2, 2, &[], &RuleSet::default(), 0), None);
+}
@@ -0,0 +1,72 @@
+use super::super::components::{Coord, Nodes, TileMap};
+use super::rules::RuleSet;
+use super::solver::{SichuanSolver, SolverError};
+use super::utils::get_node_edges;
+
+pub fn get_first_move(nodes: &[Nodes], rules: &RuleSet) -> Option<[Coord; 2]> {
+    /*
+     * The first move of any full solution keeps the board solvable. The pair
+     * is given in the coordinates of the map, while the solver works on the
+     * padded board.
+     */
+    let padding = rules.padding();
+    nodes
+        .first()
+        .map(|node| get_node_edges(node).map(|[x, y]| [x - padding, y - padding]))
+}
+
+pub fn get_hint(
+    map: &TileMap,
+    size: &[usize; 2],
+    rules: &RuleSet,
+    max_steps: Option<usize>,
+) -> Result<Option<[Coord; 2]>, SolverError> {
+    let mut solver = SichuanSolver::new(map, size, rules);
+    solver.set_max_steps(max_steps);
+    solver.solve()?;
+    Ok(get_first_move(&solver.result(), rules))
+}
+
+#[test]
+fn test_get_hint() {
+    use super::super::components::Coord;
+    use std::collections::HashMap;
+    /*
+     * 1 0 1
+     * 0 2 2
+     */
+    let map: TileMap = HashMap::from([
+        ([0, 0], 1),
+        ([1, 0], 0),
+        ([2, 0], 1),
+        ([0, 1], 0),
+        ([1, 1], 2),
+        ([2, 1], 2),
+    ]);
+    let inside = RuleSet {
+        allow_outside: false,
+        ..RuleSet::default()
+    };
+    let pair = get_hint(&map, &[3, 2], &inside, None).unwrap().unwrap();
+    assert_eq!(pair, [[1, 1], [2, 1]]);
+
+    let mut next: TileMap = map.clone();
+    pair.iter().for_each(|coord: &Coord| {
+        next.remove(coord);
+    });
+    assert_eq!(
+        get_hint(&next, &[3, 2], &inside, None).map(|pair| pair.is_some()),
+        Ok(true)
+    );
+    assert_eq!(get_hint(&HashMap::new(), &[3, 2], &inside, None), Ok(None));
+
+    /*
+     * 0 1
+     * 1 0
+     */
+    let map: TileMap = HashMap::from([([0, 0], 0), ([1, 0], 1), ([0, 1], 1), ([1, 1], 0)]);
+    assert_eq!(
+        get_hint(&map, &[2, 2], &inside, None),
+        Err(SolverError::Deadlock(vec![0, 1]))
+    );
+}
@@ -1,24 +1,41 @@
-use super::super::components::{Coord, Tile, TileMap};
+#[cfg(test)]
+use super::super::components::TileMap;
+use super::super::components::{Coord, Tile};
+use super::board::Board;
+use super::classes::TileClasses;
 use std::collections::HashMap;
 
 pub type CoordCollection = HashMap<Tile, Vec<Coord>>;
 pub type CoordPairCollection = HashMap<Tile, Vec<[Coord; 2]>>;
 
-pub fn tile_map_to_coord_collection(map: &TileMap) -> CoordCollection {
+fn to_coord_collection(
+    tiles: impl Iterator<Item = (Coord, Tile)>,
+    classes: &TileClasses,
+) -> CoordCollection {
     let mut collection: CoordCollection = HashMap::new();
-    for (&coord, &tile) in map.iter() {
-        match collection.get_mut(&tile) {
+    for (coord, tile) in tiles {
+        let class = classes.class(tile);
+        match collection.get_mut(&class) {
             Some(coords) => {
                 coords.push(coord);
             }
             _ => {
-                collection.insert(tile, vec![coord]);
+                collection.insert(class, vec![coord]);
             }
         }
     }
     collection
 }
 
+#[cfg(test)]
+pub fn tile_map_to_coord_collection(map: &TileMap, classes: &TileClasses) -> CoordCollection {
+    to_coord_collection(map.iter().map(|(&coord, &tile)| (coord, tile)), classes)
+}
+
+pub fn board_to_coord_collection(board: &Board, classes: &TileClasses) -> CoordCollection {
+    to_coord_collection(board.iter(), classes)
+}
+
 fn create_coord_pair(coords: &Vec<Coord>) -> Vec<[Coord; 2]> {
     let ordered_coords: Vec<Coord> = coords.clone().into_iter().collect();
     let mut pairs: Vec<[Coord; 2]> = Vec::new();
@@ -41,7 +58,7 @@ pub fn create_coord_pair_collection(lut: &CoordCollection) -> CoordPairCollectio
 #[test]
 fn test_tile_map_to_coord_collection() {
     let map: TileMap = HashMap::from([([0, 0], 0), ([1, 1], 1), ([2, 3], 2), ([1, 0], 1)]);
-    let mut actual = tile_map_to_coord_collection(&map);
+    let mut actual = tile_map_to_coord_collection(&map, &TileClasses::default());
     for coords in actual.values_mut() {
         coords.sort();
     }
@@ -59,6 +76,18 @@ fn test_tile_map_to_coord_collection() {
     assert_eq!(actual3, expected3);
 }
 
+#[test]
+fn test_board_to_coord_collection() {
+    let map: TileMap = HashMap::from([([0, 0], 0), ([1, 1], 1), ([2, 3], 2), ([1, 0], 1)]);
+    let classes = TileClasses::default();
+    let mut expected = tile_map_to_coord_collection(&map, &classes);
+    let mut actual = board_to_coord_collection(&Board::from_map(&map, &[3, 4]), &classes);
+    for coords in expected.values_mut().chain(actual.values_mut()) {
+        coords.sort();
+    }
+    assert_eq!(actual, expected);
+}
+
 #[test]
 fn test_create_coord_pair() {
     let coords: Vec<Coord> = vec![[0, 0], [1, 0], [0, 1], [1, 1]];
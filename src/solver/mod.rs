@@ -1,6 +1,15 @@
+pub mod board;
+pub mod classes;
+pub mod connect;
+pub mod deadlock;
+pub mod difficulty;
+pub mod generator;
+pub mod hint;
+pub mod ordering;
+pub mod progress;
+pub mod rules;
 pub mod solver;
 pub mod utils;
 
-mod connect;
 mod direction;
 mod lut;
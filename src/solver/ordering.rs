@@ -0,0 +1,192 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::super::components::{Nodes, Tile};
+use super::board::Board;
+use super::rules::RuleSet;
+use super::utils::{get_node_edges, get_node_length, SeededRng};
+
+pub trait MoveOrdering {
+    /*
+     * `connections` come grouped by tile class in ascending order, and sorted
+     * by their end coordinates within each class. The solver tries them in
+     * the order left behind.
+     */
+    fn order(&mut self, board: &Board, rules: &RuleSet, connections: &mut Vec<Nodes>);
+}
+
+impl<O: MoveOrdering + ?Sized> MoveOrdering for Box<O> {
+    fn order(&mut self, board: &Board, rules: &RuleSet, connections: &mut Vec<Nodes>) {
+        (**self).order(board, rules, connections)
+    }
+}
+
+fn get_class(node: &Nodes, board: &Board, rules: &RuleSet) -> Tile {
+    let [coord, _] = get_node_edges(node);
+    rules.classes.class(board.get(&coord).unwrap())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FewestRemaining;
+
+impl MoveOrdering for FewestRemaining {
+    fn order(&mut self, board: &Board, rules: &RuleSet, connections: &mut Vec<Nodes>) {
+        /*
+         * Connection order:
+         * 1. The fewer remaining tiles comes earlier
+         * 2. Tile which has more possible connections comes earlier
+         * 3. The fewer tile values comes earlier
+         */
+        let mut n_tiles: HashMap<Tile, usize> = HashMap::new();
+        for (_, tile) in board.iter() {
+            *n_tiles.entry(rules.classes.class(tile)).or_insert(0) += 1;
+        }
+        let mut n_connections: HashMap<Tile, usize> = HashMap::new();
+        for node in connections.iter() {
+            *n_connections
+                .entry(get_class(node, board, rules))
+                .or_insert(0) += 1;
+        }
+
+        connections.sort_by_key(|node| {
+            let class = get_class(node, board, rules);
+            (n_tiles[&class], Reverse(n_connections[&class]), class)
+        });
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FewestBlocking;
+
+impl MoveOrdering for FewestBlocking {
+    fn order(&mut self, board: &Board, rules: &RuleSet, connections: &mut Vec<Nodes>) {
+        /*
+         * A pair with few tiles in the rectangle spanned by its two ends sits
+         * apart from the rest, so taking it disturbs the fewest other paths.
+         */
+        let tiles: Vec<_> = board.iter().map(|(coord, _)| coord).collect();
+        connections.sort_by_key(|node| {
+            let [[x1, y1], [x2, y2]] = get_node_edges(node);
+            let (xs, ys) = (x1.min(x2)..=x1.max(x2), y1.min(y2)..=y1.max(y2));
+            let n_blocking = tiles
+                .iter()
+                .filter(|[x, y]| xs.contains(x) && ys.contains(y))
+                .count();
+            (n_blocking, get_class(node, board, rules))
+        });
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ShortestPath;
+
+impl MoveOrdering for ShortestPath {
+    fn order(&mut self, _board: &Board, _rules: &RuleSet, connections: &mut Vec<Nodes>) {
+        connections.sort_by_key(get_node_length);
+    }
+}
+
+pub struct SeededRandom {
+    rng: SeededRng,
+}
+
+impl SeededRandom {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: SeededRng::new(seed),
+        }
+    }
+}
+
+impl MoveOrdering for SeededRandom {
+    fn order(&mut self, _board: &Board, _rules: &RuleSet, connections: &mut Vec<Nodes>) {
+        self.rng.shuffle(connections);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MoveOrderingKind {
+    #[default]
+    FewestRemaining,
+    FewestBlocking,
+    ShortestPath,
+    Random(u64),
+}
+
+impl MoveOrderingKind {
+    pub fn create(&self) -> Box<dyn MoveOrdering> {
+        match *self {
+            Self::FewestRemaining => Box::new(FewestRemaining),
+            Self::FewestBlocking => Box::new(FewestBlocking),
+            Self::ShortestPath => Box::new(ShortestPath),
+            Self::Random(seed) => Box::new(SeededRandom::new(seed)),
+        }
+    }
+}
+
+#[test]
+fn test_move_orderings() {
+    use super::super::components::TileMap;
+    /*
+     * 0 1 x 0
+     * 2 x x 1
+     * 2 x x x
+     */
+    let map: TileMap = HashMap::from([
+        ([0, 0], 0),
+        ([1, 0], 1),
+        ([3, 0], 0),
+        ([3, 1], 1),
+        ([0, 1], 2),
+        ([0, 2], 2),
+    ]);
+    let board = Board::from_map(&map, &[4, 3]);
+    let rules = RuleSet::default();
+    let connections: Vec<Nodes> = vec![
+        vec![[0, 0], [3, 0]],
+        vec![[1, 0], [1, 1], [3, 1]],
+        vec![[0, 1], [0, 2]],
+    ];
+
+    let mut actual = connections.clone();
+    FewestRemaining.order(&board, &rules, &mut actual);
+    assert_eq!(actual, connections);
+
+    let mut actual = connections.clone();
+    FewestBlocking.order(&board, &rules, &mut actual);
+    assert_eq!(
+        actual,
+        vec![
+            vec![[0, 1], [0, 2]],
+            vec![[0, 0], [3, 0]],
+            vec![[1, 0], [1, 1], [3, 1]],
+        ]
+    );
+
+    let mut actual = connections.clone();
+    ShortestPath.order(&board, &rules, &mut actual);
+    assert_eq!(
+        actual,
+        vec![
+            vec![[0, 1], [0, 2]],
+            vec![[0, 0], [3, 0]],
+            vec![[1, 0], [1, 1], [3, 1]],
+        ]
+    );
+
+    let shuffle = |seed| {
+        let mut actual = connections.clone();
+        MoveOrderingKind::Random(seed)
+            .create()
+            .order(&board, &rules, &mut actual);
+        actual
+    };
+    assert_eq!(shuffle(3), shuffle(3));
+    let mut shuffled = shuffle(3);
+    shuffled.sort();
+    let mut expected = connections.clone();
+    expected.sort();
+    assert_eq!(shuffled, expected);
+}
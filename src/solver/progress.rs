@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SolverProgressReport {
+    // [cursor, number of connections] of each snapshot, from the root.
+    pub cursors: Vec<[usize; 2]>,
+    pub n_tiles: usize,
+    pub n_remaining: usize,
+    pub n_best_remaining: usize,
+    pub n_visited: usize,
+    pub nodes_per_second: f64,
+}
+
+impl SolverProgressReport {
+    pub fn depth(&self) -> usize {
+        self.cursors.len()
+    }
+
+    pub fn explored_ratio(&self) -> f64 {
+        /*
+         * Every connection at a depth is weighted as an equal share of its
+         * parent, so the branches passed over at each depth add up to the
+         * part of the search tree already done.
+         */
+        let mut ratio = 0.0;
+        let mut weight = 1.0;
+        for &[cursor, n_connections] in self.cursors.iter() {
+            weight /= n_connections as f64;
+            ratio += weight * cursor as f64;
+        }
+        ratio
+    }
+}
+
+pub trait ProgressObserver {
+    fn on_progress(&mut self, report: &SolverProgressReport);
+}
+
+impl<F> ProgressObserver for F
+where
+    F: FnMut(&SolverProgressReport),
+{
+    fn on_progress(&mut self, report: &SolverProgressReport) {
+        self(report)
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(super) fn now_ms() -> f64 {
+    js_sys::Date::now()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(super) fn now_ms() -> f64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0.0, |elapsed| elapsed.as_secs_f64() * 1000.0)
+}
+
+#[test]
+fn test_explored_ratio() {
+    let report = SolverProgressReport {
+        cursors: vec![[1, 2], [2, 4]],
+        n_tiles: 8,
+        n_remaining: 4,
+        n_best_remaining: 2,
+        n_visited: 10,
+        nodes_per_second: 0.0,
+    };
+    assert_eq!(report.depth(), 2);
+    assert_eq!(report.explored_ratio(), 0.5 + 0.5 * 0.25 * 2.0);
+}
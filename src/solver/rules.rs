@@ -0,0 +1,85 @@
+use std::cmp::max;
+
+use serde::{Deserialize, Serialize};
+
+use super::super::components::TileMap;
+use super::board::Board;
+use super::classes::TileClasses;
+use super::utils::{get_size_from_map, pad_tilemap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleSet {
+    pub max_turns: usize,
+    pub allow_outside: bool,
+    pub gravity: bool,
+    pub classes: TileClasses,
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        Self {
+            max_turns: 2,
+            allow_outside: true,
+            gravity: false,
+            classes: TileClasses::default(),
+        }
+    }
+}
+
+impl RuleSet {
+    pub fn padding(&self) -> usize {
+        match self.allow_outside {
+            true => 1,
+            false => 0,
+        }
+    }
+
+    pub fn pad_map(&self, map: &TileMap) -> TileMap {
+        match self.allow_outside {
+            true => pad_tilemap(map),
+            false => map.clone(),
+        }
+    }
+
+    pub fn create_board(&self, map: &TileMap, size: &[usize; 2]) -> Board {
+        /*
+         * `size` is the board as laid out in the editor, whose empty rows and
+         * columns may still carry paths. Paths may go one cell beyond every
+         * edge when `allow_outside` is set, so the board gets a margin on all
+         * sides.
+         */
+        let [map_width, map_height] = get_size_from_map(map);
+        let [width, height] = [max(size[0], map_width), max(size[1], map_height)];
+        let padding = self.padding();
+        Board::from_map(
+            &self.pad_map(map),
+            &[width + padding * 2, height + padding * 2],
+        )
+    }
+}
+
+#[test]
+fn test_create_board() {
+    use std::collections::HashMap;
+    let map: TileMap = HashMap::from([([0, 0], 0), ([2, 1], 0)]);
+
+    let board = RuleSet::default().create_board(&map, &[0, 0]);
+    assert_eq!(board.size(), [5, 4]);
+    assert_eq!(board.to_map(), pad_tilemap(&map));
+
+    let board = RuleSet::default().create_board(&map, &[4, 3]);
+    assert_eq!(board.size(), [6, 5]);
+    assert_eq!(board.to_map(), pad_tilemap(&map));
+
+    let rules = RuleSet {
+        allow_outside: false,
+        ..RuleSet::default()
+    };
+    let board = rules.create_board(&map, &[3, 2]);
+    assert_eq!(board.size(), [3, 2]);
+    assert_eq!(board.to_map(), map);
+
+    let board = rules.create_board(&map, &[4, 3]);
+    assert_eq!(board.size(), [4, 3]);
+    assert_eq!(board.to_map(), map);
+}
@@ -1,20 +1,139 @@
+#[cfg(test)]
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
 
 use super::super::components::{Coord, Nodes, Tile, TileMap};
-use super::connect::try_get_node_connection;
-use super::lut::{create_coord_pair_collection, tile_map_to_coord_collection, CoordCollection};
-use super::utils::{get_node_edges, get_size_from_map, pad_tilemap};
+#[cfg(test)]
+use super::super::notation::parse_board;
+use super::board::{Board, BoardKey};
+use super::connect::{try_get_node_connection, PathSelection};
+use super::deadlock::find_deadlock;
+use super::lut::{board_to_coord_collection, create_coord_pair_collection, CoordCollection};
+use super::ordering::{FewestRemaining, MoveOrdering};
+use super::progress::{now_ms, ProgressObserver, SolverProgressReport};
+use super::rules::RuleSet;
+use super::utils::{get_node_coords, get_node_edges};
 
-fn remove_tiles(map: &TileMap, nodes: &Vec<Nodes>) -> TileMap {
-    let mut new_map = map.clone();
+fn remove_tiles(board: &Board, nodes: &[Nodes]) -> Board {
+    let mut new_board = board.clone();
     nodes
         .iter()
         .map(|nodes| get_node_edges(&nodes))
         .flatten()
         .for_each(|coord| {
-            new_map.remove(&coord);
+            new_board.remove(&coord);
         });
-    new_map
+    new_board
+}
+
+fn remove_tiles_with_gravity(board: &Board, nodes: &[Nodes], top: usize) -> Board {
+    /*
+     * Pairs are removed one by one, and everything above each removed tile
+     * in its column falls down by a cell. Walls stay put and hold up the
+     * tiles resting on them.
+     */
+    let mut new_board = board.clone();
+    for node in nodes.iter() {
+        let mut edges = get_node_edges(node);
+        edges.sort_by_key(|&[_, y]| y);
+        for [x, y] in edges {
+            new_board.remove(&[x, y]);
+            for y2 in (top..y).rev() {
+                if new_board.is_wall(&[x, y2]) {
+                    break;
+                }
+                if let Some(tile) = new_board.remove(&[x, y2]) {
+                    new_board.insert(&[x, y2 + 1], tile);
+                }
+            }
+        }
+    }
+    new_board
+}
+
+fn remove_tiles_by_rules(board: &Board, nodes: &[Nodes], rules: &RuleSet) -> Board {
+    match rules.gravity {
+        true => remove_tiles_with_gravity(board, nodes, rules.padding()),
+        false => remove_tiles(board, nodes),
+    }
+}
+
+pub fn get_board_states(
+    map: &TileMap,
+    size: &[usize; 2],
+    nodes: &[Nodes],
+    rules: &RuleSet,
+) -> Vec<TileMap> {
+    let mut board = rules.create_board(map, size);
+    let mut states = vec![board.to_map()];
+    for node in nodes.iter() {
+        board = remove_tiles_by_rules(&board, std::slice::from_ref(node), rules);
+        states.push(board.to_map());
+    }
+    states
+}
+
+pub fn get_step_tiles(map: &TileMap, states: &[TileMap], nodes: &[Nodes]) -> Vec<Option<Tile>> {
+    /*
+     * The tile each step removes. With `states` from get_board_states, it is
+     * looked up where the tiles stood before that step, as they may have
+     * fallen since the start; otherwise it is looked up in `map`.
+     */
+    nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| {
+            let [coord, _] = get_node_edges(node);
+            states.get(i).unwrap_or(map).get(&coord).copied()
+        })
+        .collect()
+}
+
+pub fn route_moves(
+    map: &TileMap,
+    size: &[usize; 2],
+    moves: &[[Coord; 2]],
+    rules: &RuleSet,
+    selection: PathSelection,
+) -> Option<Vec<Nodes>> {
+    /*
+     * `moves` are the pairs removed, in the coordinates of `map`. Each pair is
+     * connected again on the board left by the pairs before it, so `None` is
+     * returned as soon as one is not a legal removal.
+     */
+    let padding = rules.padding();
+    let mut board = rules.create_board(map, size);
+    moves
+        .iter()
+        .map(|pair| {
+            let [coord1, coord2] = pair.map(|[x, y]| [x + padding, y + padding]);
+            match (board.get(&coord1), board.get(&coord2)) {
+                (Some(tile1), Some(tile2))
+                    if coord1 != coord2
+                        && !board.is_wall(&coord1)
+                        && !board.is_wall(&coord2)
+                        && rules.classes.is_matching(tile1, tile2) => {}
+                _ => return None,
+            }
+            let node = try_get_node_connection(&coord1, &coord2, &board, rules, selection)?;
+            board = remove_tiles_by_rules(&board, std::slice::from_ref(&node), rules);
+            Some(node)
+        })
+        .collect()
+}
+
+fn get_odd_tiles(lut: &CoordCollection) -> Vec<Tile> {
+    let mut tiles: Vec<Tile> = lut
+        .iter()
+        .filter(|(_, coords)| coords.len() % 2 == 1)
+        .map(|(&tile, _)| tile)
+        .collect();
+    tiles.sort();
+    tiles
 }
 
 fn get_single_pair_tiles(lut: &CoordCollection) -> Vec<[Coord; 2]> {
@@ -31,100 +150,125 @@ fn get_single_pair_tiles(lut: &CoordCollection) -> Vec<[Coord; 2]> {
     pairs
 }
 
-fn get_trivial_connections(map: &TileMap, map_size: &[usize; 2]) -> Vec<Nodes> {
-    let lut = tile_map_to_coord_collection(map);
+fn get_trivial_connections(board: &Board, rules: &RuleSet) -> Vec<Nodes> {
+    /*
+     * Removing one pair moves other tiles in gravity mode, so pairs cannot
+     * be taken together without searching.
+     */
+    if rules.gravity {
+        return Vec::new();
+    }
+
+    let lut = board_to_coord_collection(board, &rules.classes);
     let mut nodes: Vec<Nodes> = get_single_pair_tiles(&lut)
         .iter()
-        .filter_map(|[coord1, coord2]| try_get_node_connection(coord1, coord2, map, &map_size))
+        .filter_map(|[coord1, coord2]| {
+            try_get_node_connection(coord1, coord2, board, rules, PathSelection::First)
+        })
         .collect();
     nodes.sort();
     nodes
 }
 
-fn get_ordered_available_connections(map: &TileMap, map_size: &[usize; 2]) -> Vec<Nodes> {
-    /*
-     * Connection order:
-     * 1. The fewer remaining tiles comes earlier
-     * 2. Tile which has more possible connections comes earlier
-     * 3. The fewer tile values comes earlier
-     */
-    let lut = tile_map_to_coord_collection(map);
-    let mut pair_collection = create_coord_pair_collection(&lut);
-    for pairs in pair_collection.values_mut() {
+fn get_ordered_available_connections(
+    board: &Board,
+    rules: &RuleSet,
+    ordering: &mut impl MoveOrdering,
+) -> Vec<Nodes> {
+    let lut = board_to_coord_collection(board, &rules.classes);
+    let mut pair_collection: Vec<(Tile, Vec<[Coord; 2]>)> =
+        create_coord_pair_collection(&lut).into_iter().collect();
+    pair_collection.sort_by_key(|(tile, _)| *tile);
+    for (_, pairs) in pair_collection.iter_mut() {
         pairs.iter_mut().for_each(|pair| pair.sort());
         pairs.sort();
     }
 
-    let nodes: HashMap<Tile, Vec<Nodes>> = pair_collection
+    let mut connections: Vec<Nodes> = pair_collection
         .iter()
-        .map(|(&tile, pairs)| {
-            (
-                tile,
-                pairs
-                    .iter()
-                    .filter_map(|[c1, c2]| try_get_node_connection(c1, c2, map, map_size))
-                    .collect::<Vec<Nodes>>(),
-            )
-        })
+        .flat_map(|(_, pairs)| pairs.iter())
+        .filter_map(|[c1, c2]| try_get_node_connection(c1, c2, board, rules, PathSelection::First))
         .collect();
+    ordering.order(board, rules, &mut connections);
+    connections
+}
 
-    let mut tiles: Vec<Tile> = nodes.iter().map(|(k, _)| k.clone()).collect();
-    tiles.sort_unstable_by(|a, b| {
-        let (n_coords_a, n_coords_b) = (lut[&a].len(), lut[&b].len());
-        if n_coords_a != n_coords_b {
-            return n_coords_a.partial_cmp(&n_coords_b).unwrap();
-        }
+fn is_commutable(nodes1: &Nodes, nodes2: &Nodes) -> bool {
+    let (edges1, edges2) = (get_node_edges(nodes1), get_node_edges(nodes2));
+    let (coords1, coords2) = (get_node_coords(nodes1), get_node_coords(nodes2));
+    !edges2.iter().any(|coord| coords1.contains(coord))
+        && !edges1.iter().any(|coord| coords2.contains(coord))
+}
 
-        let (n_nodes_a, n_nodes_b) = (nodes[&a].len(), nodes[&b].len());
-        if n_nodes_a != n_nodes_b {
-            return n_nodes_b.partial_cmp(&n_nodes_a).unwrap();
-        }
+fn get_commutation_key(nodes: &[Nodes]) -> Vec<Vec<[Coord; 2]>> {
+    /*
+     * Foata normal form: each removal is placed one level after the latest
+     * removal it cannot be swapped with, so every reordering of commuting
+     * removals ends up with the same levels.
+     */
+    let mut levels: Vec<usize> = Vec::new();
+    for (i, node) in nodes.iter().enumerate() {
+        let level = (0..i)
+            .filter(|&j| !is_commutable(&nodes[j], node))
+            .map(|j| levels[j] + 1)
+            .max()
+            .unwrap_or(0);
+        levels.push(level);
+    }
 
-        a.partial_cmp(&b).unwrap()
-    });
-    tiles
-        .iter()
-        .map(|tile| nodes[&tile].clone())
-        .flatten()
-        .collect()
+    let mut key: Vec<Vec<[Coord; 2]>> = vec![Vec::new(); levels.iter().max().map_or(0, |l| l + 1)];
+    for (node, &level) in nodes.iter().zip(levels.iter()) {
+        let mut edges = get_node_edges(node);
+        edges.sort();
+        key[level].push(edges);
+    }
+    key.iter_mut().for_each(|pairs| pairs.sort());
+    key
 }
 
 #[derive(Clone)]
 struct SichuanSolverSnapshot {
-    map: TileMap,
-    map_size: [usize; 2],
+    map: Board,
+    rules: RuleSet,
+    key: BoardKey,
     connections: Vec<Nodes>,
     cursor: usize,
     resolved: Vec<Nodes>,
+    solved: bool,
 }
 
 impl SichuanSolverSnapshot {
-    fn try_new(map: &TileMap, map_size: &[usize; 2]) -> Option<Self> {
-        let connections = get_ordered_available_connections(map, map_size);
+    fn try_new(map: &Board, rules: &RuleSet, ordering: &mut impl MoveOrdering) -> Option<Self> {
+        let connections = get_ordered_available_connections(map, rules, ordering);
         match connections.len() {
             0 => None,
             _ => Some(Self {
                 map: map.clone(),
-                map_size: map_size.clone(),
+                rules: *rules,
+                key: map.key(),
                 connections,
                 cursor: 0,
                 resolved: Vec::new(),
+                solved: false,
             }),
         }
     }
 
-    fn resolve(&mut self) {
+    fn resolve(&mut self, take_forced: bool) {
         let assumed = &self.connections[self.cursor];
-        let mut map = remove_tiles(&self.map, &vec![assumed.to_vec()]);
+        let mut map = remove_tiles_by_rules(&self.map, &[assumed.to_vec()], &self.rules);
 
         self.resolved.clear();
+        if !take_forced {
+            return;
+        }
         loop {
-            let resolved = get_trivial_connections(&map, &self.map_size);
+            let resolved = get_trivial_connections(&map, &self.rules);
             if resolved.len() == 0 {
                 break;
             }
 
-            map = remove_tiles(&map, &resolved);
+            map = remove_tiles_by_rules(&map, &resolved, &self.rules);
             self.resolved.extend(resolved);
         }
     }
@@ -146,60 +290,211 @@ impl SichuanSolverSnapshot {
     }
 }
 
-pub struct SichuanSolver {
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SolverDiagnostics {
+    pub deepest: Vec<Nodes>,
+    pub remaining: TileMap,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SolverError {
+    OddTileCount(Vec<Tile>),
+    Deadlock(Vec<Tile>),
+    NoLegalFirstMove(SolverDiagnostics),
+    SearchExhausted(SolverDiagnostics),
+    BudgetExceeded(SolverDiagnostics),
+}
+
+impl SolverError {
+    pub fn diagnostics(&self) -> Option<&SolverDiagnostics> {
+        match self {
+            Self::OddTileCount(_) | Self::Deadlock(_) => None,
+            Self::NoLegalFirstMove(diagnostics)
+            | Self::SearchExhausted(diagnostics)
+            | Self::BudgetExceeded(diagnostics) => Some(diagnostics),
+        }
+    }
+}
+
+impl fmt::Display for SolverError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::OddTileCount(tiles) => {
+                write!(
+                    f,
+                    "{} tile kind(s) have an odd number of copies",
+                    tiles.len()
+                )
+            }
+            Self::Deadlock(tiles) => {
+                write!(
+                    f,
+                    "{} tile kind(s) block each other and can never be removed",
+                    tiles.len()
+                )
+            }
+            Self::NoLegalFirstMove(_) => write!(f, "No pair of tiles can be connected"),
+            Self::SearchExhausted(_) => write!(f, "Every removal order ends in a dead end"),
+            Self::BudgetExceeded(_) => write!(f, "The search was aborted after too many steps"),
+        }
+    }
+}
+
+impl Error for SolverError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolverProgress {
+    Pending,
+    Solved,
+    Failed(SolverError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SolverMetrics {
+    pub n_snapshots: usize,
+    pub n_branch_points: usize,
+    pub n_backtracks: usize,
+    pub n_options: usize,
+    pub n_moves: usize,
+    pub n_forced_moves: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SolutionOptions {
+    pub limit: Option<usize>,
+    pub skip_commuting: bool,
+}
+
+pub struct SichuanSolver<O: MoveOrdering = FewestRemaining> {
     snapshots: Vec<SichuanSolverSnapshot>,
-    initial_map: TileMap,
+    initial_map: Board,
     first_resolved: Vec<Nodes>,
+    deadlock: Option<Vec<Tile>>,
+    deepest: Vec<Nodes>,
+    dead_states: HashSet<BoardKey>,
+    take_forced: bool,
+    n_steps: usize,
+    max_steps: Option<usize>,
+    path_selection: PathSelection,
+    rules: RuleSet,
+    metrics: SolverMetrics,
+    observer: Option<(Box<dyn ProgressObserver>, usize)>,
+    started_at: Option<f64>,
+    ordering: O,
 }
 
 impl SichuanSolver {
-    pub fn new(map: &TileMap) -> Self {
-        let pad_map = pad_tilemap(map);
-        let map_size = get_size_from_map(&pad_map);
-        let resolved = get_trivial_connections(&pad_map, &map_size);
-        match SichuanSolverSnapshot::try_new(&remove_tiles(&pad_map, &resolved), &map_size) {
-            Some(mut snapshot) => {
-                snapshot.resolve();
-                Self {
-                    snapshots: vec![snapshot],
-                    initial_map: pad_map,
-                    first_resolved: resolved,
-                }
-            }
-            _ => Self {
-                snapshots: Vec::new(),
-                initial_map: pad_map,
-                first_resolved: resolved,
-            },
+    pub fn new(map: &TileMap, size: &[usize; 2], rules: &RuleSet) -> Self {
+        Self::with_ordering(map, size, rules, FewestRemaining)
+    }
+}
+
+impl<O: MoveOrdering> SichuanSolver<O> {
+    pub fn with_ordering(map: &TileMap, size: &[usize; 2], rules: &RuleSet, ordering: O) -> Self {
+        let mut solver = Self {
+            snapshots: Vec::new(),
+            initial_map: rules.create_board(map, size),
+            first_resolved: Vec::new(),
+            deadlock: None,
+            deepest: Vec::new(),
+            dead_states: HashSet::new(),
+            take_forced: true,
+            n_steps: 0,
+            max_steps: None,
+            path_selection: PathSelection::First,
+            rules: *rules,
+            metrics: SolverMetrics::default(),
+            observer: None,
+            started_at: None,
+            ordering,
+        };
+        solver.start();
+        solver
+    }
+
+    fn start(&mut self) {
+        let resolved = match self.take_forced {
+            true => get_trivial_connections(&self.initial_map, &self.rules),
+            false => Vec::new(),
+        };
+        let resolved_board = remove_tiles_by_rules(&self.initial_map, &resolved, &self.rules);
+        self.snapshots.clear();
+        self.first_resolved = resolved.clone();
+        self.deadlock = find_deadlock(&resolved_board, &self.rules);
+        self.deepest = resolved;
+        if self.deadlock.is_some() {
+            return;
+        }
+
+        if let Some(mut snapshot) =
+            SichuanSolverSnapshot::try_new(&resolved_board, &self.rules, &mut self.ordering)
+        {
+            snapshot.resolve(self.take_forced);
+            self.record_snapshot(&snapshot);
+            self.snapshots.push(snapshot);
+            self.update_deepest();
         }
     }
 
     fn add_snapshot(&mut self) -> Result<(), ()> {
         match self.snapshots.last() {
             Some(latest_snapshot) => {
-                let map = remove_tiles(&latest_snapshot.map, &latest_snapshot.nodes());
-                match SichuanSolverSnapshot::try_new(&map, &latest_snapshot.map_size) {
+                let map = remove_tiles_by_rules(
+                    &latest_snapshot.map,
+                    &latest_snapshot.nodes(),
+                    &self.rules,
+                );
+                let key = map.key();
+                if self.dead_states.contains(&key) {
+                    return Err(());
+                }
+
+                if find_deadlock(&map, &self.rules).is_some() {
+                    self.dead_states.insert(key);
+                    return Err(());
+                }
+
+                match SichuanSolverSnapshot::try_new(&map, &self.rules, &mut self.ordering) {
                     Some(mut snapshot) => {
-                        snapshot.resolve();
+                        snapshot.resolve(self.take_forced);
+                        self.record_snapshot(&snapshot);
                         self.snapshots.push(snapshot);
                         Ok(())
                     }
-                    _ => Err(()),
+                    _ => {
+                        if !map.is_empty() {
+                            self.dead_states.insert(key);
+                        }
+                        Err(())
+                    }
                 }
             }
             _ => Err(()),
         }
     }
 
+    fn record_snapshot(&mut self, snapshot: &SichuanSolverSnapshot) {
+        let n_options = snapshot.connections.len();
+        self.metrics.n_snapshots += 1;
+        self.metrics.n_options += n_options;
+        if n_options > 1 {
+            self.metrics.n_branch_points += 1;
+        }
+    }
+
     fn update_snapshot(&mut self) -> Result<(), ()> {
+        self.metrics.n_backtracks += 1;
         loop {
             match self.snapshots.last_mut() {
                 Some(latest_snapshot) => match latest_snapshot.next() {
                     Ok(()) => {
-                        latest_snapshot.resolve();
+                        latest_snapshot.resolve(self.take_forced);
                         return Ok(());
                     }
                     Err(()) => {
+                        if !latest_snapshot.solved {
+                            self.dead_states.insert(latest_snapshot.key.clone());
+                        }
                         self.snapshots.pop();
                     }
                 },
@@ -210,29 +505,234 @@ impl SichuanSolver {
 
     fn is_completed(&self) -> bool {
         match self.snapshots.last() {
-            Some(latest_snapshot) => remove_tiles(&latest_snapshot.map, &latest_snapshot.nodes()),
-            _ => remove_tiles(&self.initial_map, &self.first_resolved),
+            Some(latest_snapshot) => {
+                remove_tiles_by_rules(&latest_snapshot.map, &latest_snapshot.nodes(), &self.rules)
+            }
+            _ => remove_tiles_by_rules(&self.initial_map, &self.first_resolved, &self.rules),
         }
-        .len()
-            == 0
+        .is_empty()
     }
 
-    pub fn solve(&mut self) -> Result<(), ()> {
-        loop {
-            if self.add_snapshot().is_err() {
-                match self.is_completed() {
-                    true => return Ok(()),
-                    false => {
-                        if self.update_snapshot().is_err() {
-                            return Err(());
-                        }
-                    }
+    fn update_deepest(&mut self) {
+        let n_resolved: usize = self
+            .snapshots
+            .iter()
+            .map(|snapshot| snapshot.resolved.len() + 1)
+            .sum();
+        if self.first_resolved.len() + n_resolved > self.deepest.len() {
+            self.deepest = self.nodes();
+        }
+    }
+
+    fn diagnostics(&self) -> SolverDiagnostics {
+        SolverDiagnostics {
+            deepest: self.route(&self.deepest),
+            remaining: remove_tiles_by_rules(&self.initial_map, &self.deepest, &self.rules)
+                .iter()
+                .collect(),
+        }
+    }
+
+    fn check_solvable(&self) -> Result<(), SolverError> {
+        let odd_tiles = get_odd_tiles(&board_to_coord_collection(
+            &self.initial_map,
+            &self.rules.classes,
+        ));
+        if !odd_tiles.is_empty() {
+            return Err(SolverError::OddTileCount(odd_tiles));
+        }
+
+        if let Some(tiles) = &self.deadlock {
+            return Err(SolverError::Deadlock(tiles.clone()));
+        }
+
+        match self.snapshots.is_empty() && self.first_resolved.is_empty() && !self.is_completed() {
+            true => Err(SolverError::NoLegalFirstMove(self.diagnostics())),
+            false => Ok(()),
+        }
+    }
+
+    fn step(&mut self) -> Option<Result<(), SolverError>> {
+        if self.add_snapshot().is_ok() {
+            self.update_deepest();
+            return None;
+        }
+
+        match self.is_completed() {
+            true => {
+                self.snapshots
+                    .iter_mut()
+                    .for_each(|snapshot| snapshot.solved = true);
+                Some(Ok(()))
+            }
+            false => match self.update_snapshot() {
+                Ok(()) => {
+                    self.update_deepest();
+                    None
                 }
+                Err(()) => Some(Err(SolverError::SearchExhausted(self.diagnostics()))),
+            },
+        }
+    }
+
+    pub fn set_max_steps(&mut self, max_steps: Option<usize>) {
+        self.max_steps = max_steps;
+    }
+
+    pub fn set_path_selection(&mut self, path_selection: PathSelection) {
+        self.path_selection = path_selection;
+    }
+
+    pub fn set_progress_observer(&mut self, observer: Box<dyn ProgressObserver>, interval: usize) {
+        self.observer = Some((observer, interval.max(1)));
+    }
+
+    pub fn progress(&self) -> SolverProgressReport {
+        let n_tiles = self.initial_map.len();
+        let elapsed_ms = self
+            .started_at
+            .map_or(0.0, |started_at| now_ms() - started_at);
+        SolverProgressReport {
+            cursors: self
+                .snapshots
+                .iter()
+                .map(|snapshot| [snapshot.cursor, snapshot.connections.len()])
+                .collect(),
+            n_tiles,
+            n_remaining: n_tiles - self.nodes().len() * 2,
+            n_best_remaining: n_tiles - self.deepest.len() * 2,
+            n_visited: self.n_steps,
+            nodes_per_second: match elapsed_ms > 0.0 {
+                true => self.n_steps as f64 * 1000.0 / elapsed_ms,
+                false => 0.0,
+            },
+        }
+    }
+
+    fn report_progress(&mut self) {
+        if matches!(&self.observer, Some((_, interval)) if self.n_steps.is_multiple_of(*interval)) {
+            let report = self.progress();
+            if let Some((observer, _)) = self.observer.as_mut() {
+                observer.on_progress(&report);
+            }
+        }
+    }
+
+    pub fn solve_for(&mut self, max_steps: usize) -> SolverProgress {
+        if let Err(err) = self.check_solvable() {
+            return SolverProgress::Failed(err);
+        }
+        if self.started_at.is_none() {
+            self.started_at = Some(now_ms());
+        }
+
+        for _ in 0..max_steps {
+            if let Some(result) = self.step() {
+                return match result {
+                    Ok(()) => SolverProgress::Solved,
+                    Err(err) => SolverProgress::Failed(err),
+                };
+            }
+
+            self.n_steps += 1;
+            self.report_progress();
+            if matches!(self.max_steps, Some(max_steps) if self.n_steps >= max_steps) {
+                return SolverProgress::Failed(SolverError::BudgetExceeded(self.diagnostics()));
             }
         }
+        SolverProgress::Pending
+    }
+
+    pub fn solve(&mut self) -> Result<(), SolverError> {
+        loop {
+            match self.solve_for(usize::MAX) {
+                SolverProgress::Pending => continue,
+                SolverProgress::Solved => return Ok(()),
+                SolverProgress::Failed(err) => return Err(err),
+            }
+        }
+    }
+
+    pub fn solutions(&mut self, options: SolutionOptions) -> SichuanSolutions<'_, O> {
+        /*
+         * Forced pairs are taken together without a choice, which hides the
+         * orders they could be removed in. The search is restarted with every
+         * pair as a choice when those orders are told apart. With
+         * `skip_commuting` they are still taken together, so a class whose
+         * only members remove a forced pair late may be missed.
+         *
+         * A removal moves the tiles above it in gravity mode, so removals
+         * whose paths do not touch still do not commute there.
+         */
+        let options = SolutionOptions {
+            skip_commuting: options.skip_commuting && !self.rules.gravity,
+            ..options
+        };
+        if !options.skip_commuting && self.take_forced {
+            self.take_forced = false;
+            self.start();
+        }
+        SichuanSolutions {
+            solver: self,
+            options,
+            seen: HashSet::new(),
+            n_found: 0,
+            backtrack: false,
+            exhausted: false,
+        }
+    }
+
+    fn route(&self, nodes: &[Nodes]) -> Vec<Nodes> {
+        /*
+         * The search keeps whichever path it found first. Replay the removals
+         * to pick the path of each pair again under the requested selection.
+         */
+        if self.path_selection == PathSelection::First {
+            return nodes.to_vec();
+        }
+
+        let mut board = self.initial_map.clone();
+        nodes
+            .iter()
+            .map(|node| {
+                let [coord1, coord2] = get_node_edges(node);
+                let routed = try_get_node_connection(
+                    &coord1,
+                    &coord2,
+                    &board,
+                    &self.rules,
+                    self.path_selection,
+                )
+                .unwrap_or_else(|| node.clone());
+                board = remove_tiles_by_rules(&board, std::slice::from_ref(&routed), &self.rules);
+                routed
+            })
+            .collect()
+    }
+
+    pub fn metrics(&self) -> SolverMetrics {
+        /*
+         * Moves are counted on the current line of the search, which is the
+         * solution once `solve` has succeeded. Forced moves are the pairs
+         * taken by `get_trivial_connections` without a choice.
+         */
+        let n_resolved: usize = self
+            .snapshots
+            .iter()
+            .map(|snapshot| snapshot.resolved.len())
+            .sum();
+        SolverMetrics {
+            n_moves: self.nodes().len(),
+            n_forced_moves: self.first_resolved.len() + n_resolved,
+            ..self.metrics
+        }
     }
 
     pub fn result(&self) -> Vec<Nodes> {
+        self.route(&self.nodes())
+    }
+
+    fn nodes(&self) -> Vec<Nodes> {
         let mut nodes = self.first_resolved.clone();
         nodes.extend(
             self.snapshots
@@ -245,43 +745,185 @@ impl SichuanSolver {
     }
 }
 
+pub struct SichuanSolutions<'a, O: MoveOrdering = FewestRemaining> {
+    solver: &'a mut SichuanSolver<O>,
+    options: SolutionOptions,
+    seen: HashSet<Vec<Vec<[Coord; 2]>>>,
+    n_found: usize,
+    backtrack: bool,
+    exhausted: bool,
+}
+
+impl<'a, O: MoveOrdering> Iterator for SichuanSolutions<'a, O> {
+    type Item = Result<Vec<Nodes>, SolverError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.exhausted || matches!(self.options.limit, Some(limit) if self.n_found >= limit)
+            {
+                return None;
+            }
+
+            if self.backtrack {
+                self.backtrack = false;
+                if self.solver.update_snapshot().is_err() {
+                    self.exhausted = true;
+                    return None;
+                }
+            }
+
+            /*
+             * Running out of orders after a solution is the normal end. Any
+             * other failure, such as the step budget running out, is yielded
+             * so that it is not mistaken for the last solution.
+             */
+            if let Err(err) = self.solver.solve() {
+                self.exhausted = true;
+                return match err {
+                    SolverError::SearchExhausted(_) if self.n_found > 0 => None,
+                    err => Some(Err(err)),
+                };
+            }
+            self.backtrack = true;
+
+            /* Every order is visited once, so only commuting ones repeat. */
+            let result = self.solver.result();
+            if self.options.skip_commuting && !self.seen.insert(get_commutation_key(&result)) {
+                continue;
+            }
+            self.n_found += 1;
+            return Some(Ok(result));
+        }
+    }
+}
+
 #[test]
 fn test_remove_tiles() {
-    use std::collections::HashMap;
-    let map: TileMap = HashMap::from([
-        ([0, 0], 0),
-        ([0, 1], 1),
-        ([1, 1], 0),
-        ([1, 2], 2),
-        ([2, 1], 1),
-        ([2, 2], 2),
-    ]);
-
-    let expected1: TileMap = HashMap::from([([0, 1], 1), ([1, 2], 2), ([2, 1], 1), ([2, 2], 2)]);
+    let (map, _) = parse_board(
+        "
+        1m .  .
+        2m 1m 2m
+        .  3m 3m
+        ",
+    )
+    .unwrap();
+
+    let board = Board::from_map(&map, &[3, 3]);
+
+    let (expected1, _) = parse_board(
+        "
+        .  .  .
+        2m .  2m
+        .  3m 3m
+        ",
+    )
+    .unwrap();
+    assert_eq!(
+        remove_tiles(&board, &[vec![[0, 0], [0, 1], [1, 1]]]).to_map(),
+        expected1
+    );
+
+    let (expected2, _) = parse_board(
+        "
+        1m .  .
+        .  1m .
+        .  3m 3m
+        ",
+    )
+    .unwrap();
+    assert_eq!(
+        remove_tiles(&board, &[vec![[0, 1], [2, 1]]]).to_map(),
+        expected2
+    );
+
+    let (expected3, _) = parse_board(
+        "
+        1m .  .
+        2m 1m 2m
+        ",
+    )
+    .unwrap();
+    assert_eq!(
+        remove_tiles(&board, &[vec![[1, 2], [2, 2]]]).to_map(),
+        expected3
+    );
+}
+
+#[test]
+fn test_remove_tiles_with_gravity() {
+    let (map, _) = parse_board(
+        "
+        2m 3m
+        1m 3m
+        4m 1m
+        ",
+    )
+    .unwrap();
+    let board = Board::from_map(&map, &[3, 3]);
+
+    let (expected1, _) = parse_board(
+        "
+        .  .
+        2m 3m
+        4m 3m
+        ",
+    )
+    .unwrap();
     assert_eq!(
-        remove_tiles(&map, &vec![vec![[0, 0], [0, 1], [1, 1]]]),
+        remove_tiles_with_gravity(&board, &[vec![[0, 1], [0, 2], [1, 2]]], 0).to_map(),
         expected1
     );
 
-    let expected2: TileMap = HashMap::from([([0, 0], 0), ([1, 1], 0), ([1, 2], 2), ([2, 2], 2)]);
-    assert_eq!(remove_tiles(&map, &vec![vec![[0, 1], [2, 1]]]), expected2);
+    let (expected2, _) = parse_board(
+        "
+        .
+        2m
+        4m
+        ",
+    )
+    .unwrap();
+    assert_eq!(
+        remove_tiles_with_gravity(&board, &[vec![[1, 0], [1, 1]], vec![[0, 1], [1, 2]]], 0)
+            .to_map(),
+        expected2
+    );
 
-    let expected3: TileMap = HashMap::from([([0, 0], 0), ([0, 1], 1), ([1, 1], 0), ([2, 1], 1)]);
-    assert_eq!(remove_tiles(&map, &vec![vec![[1, 2], [2, 2]]]), expected3);
+    let (map, _) = parse_board(
+        "
+        2m 2m
+        #  .
+        1m 1m
+        ",
+    )
+    .unwrap();
+    let board = Board::from_map(&map, &[2, 3]);
+    let (expected3, _) = parse_board(
+        "
+        2m .
+        #  2m
+        ",
+    )
+    .unwrap();
+    assert_eq!(
+        remove_tiles_with_gravity(&board, &[vec![[0, 2], [1, 2]]], 0).to_map(),
+        expected3
+    );
 }
 
 #[test]
 fn test_get_single_pair_tiles() {
-    use std::collections::{HashMap, HashSet};
-    let map: TileMap = HashMap::from([
-        ([0, 0], 0),
-        ([0, 1], 1),
-        ([1, 1], 0),
-        ([1, 2], 0),
-        ([2, 1], 1),
-        ([2, 2], 0),
-    ]);
-    let lut = tile_map_to_coord_collection(&map);
+    use super::classes::TileClasses;
+    use super::lut::tile_map_to_coord_collection;
+    use std::collections::HashSet;
+    let (map, _) = parse_board(
+        "
+        1m .  .
+        2m 1m 2m
+        .  1m 1m
+        ",
+    )
+    .unwrap();
+    let lut = tile_map_to_coord_collection(&map, &TileClasses::default());
     let coords: Vec<HashSet<Coord>> = get_single_pair_tiles(&lut)
         .iter()
         .map(|pair| HashSet::from(pair.clone()))
@@ -293,36 +935,23 @@ fn test_get_single_pair_tiles() {
 
 #[test]
 fn test_get_trivial_connections() {
-    use std::collections::HashMap;
     let reverse = |nodes: &Nodes| {
         let mut reversed = nodes.clone();
         reversed.reverse();
         reversed
     };
 
-    /*
-     * 0 x 0 1
-     * 1 2 x 1
-     * 2 x 3 2
-     * 3 x 2 1
-     *
-     */
-    let map: TileMap = HashMap::from([
-        ([0, 0], 0),
-        ([0, 2], 0),
-        ([0, 3], 1),
-        ([1, 0], 1),
-        ([1, 1], 2),
-        ([1, 3], 1),
-        ([2, 0], 2),
-        ([2, 2], 3),
-        ([2, 3], 2),
-        ([3, 0], 3),
-        ([3, 2], 2),
-        ([3, 3], 1),
-    ]);
-    let map_size = [4, 4];
-    let conns = get_trivial_connections(&map, &map_size);
+    let (map, _) = parse_board(
+        "
+        1m 2m 3m 4m
+        .  3m .  .
+        1m .  4m 3m
+        2m 2m 3m 2m
+        ",
+    )
+    .unwrap();
+    let board = Board::from_map(&map, &[4, 4]);
+    let conns = get_trivial_connections(&board, &RuleSet::default());
     let expected0: Nodes = vec![[0, 0], [0, 2]];
     let expected3: Nodes = vec![[2, 2], [2, 1], [3, 1], [3, 0]];
 
@@ -342,33 +971,24 @@ fn test_get_trivial_connections() {
 #[test]
 fn test_get_ordered_available_connections() {
     /*
-     * 0 x 0 1
-     * 1 2 2 x
-     * 0 2 2 3
-     * 0 x x 3
-     *
-     * 0: 4 tiles, 2 connections
-     * 1: 2 tiles, 0 connections
-     * 2: 4 tiles, 4 connections
-     * 3: 2 tiles, 1 connections
+     * 1m: 4 tiles, 2 connections
+     * 2m: 2 tiles, 0 connections
+     * 3m: 4 tiles, 4 connections
+     * 4m: 2 tiles, 1 connections
      */
-    let map: TileMap = HashMap::from([
-        ([0, 0], 0),
-        ([0, 2], 0),
-        ([0, 3], 1),
-        ([1, 0], 1),
-        ([1, 1], 2),
-        ([1, 2], 2),
-        ([2, 0], 0),
-        ([2, 1], 2),
-        ([2, 2], 2),
-        ([2, 3], 3),
-        ([3, 0], 0),
-        ([3, 3], 3),
-    ]);
-    let map_size = [4, 4];
-
-    let actual = get_ordered_available_connections(&map, &map_size);
+    let (map, _) = parse_board(
+        "
+        1m 2m 1m 1m
+        .  3m 3m .
+        1m 3m 3m .
+        2m .  4m 4m
+        ",
+    )
+    .unwrap();
+    let board = Board::from_map(&map, &[4, 4]);
+
+    let actual =
+        get_ordered_available_connections(&board, &RuleSet::default(), &mut FewestRemaining);
     let expected = vec![
         [[2, 3], [3, 3]],
         [[1, 1], [1, 2]],
@@ -384,34 +1004,28 @@ fn test_get_ordered_available_connections() {
 #[test]
 fn test_snapshot() {
     /*
-     * 0 x 0 1
-     * 1 2 2 x
-     * 0 2 2 3
-     * 0 x x 3
-     *
-     * 0: 4 tiles, 2 connections
-     * 1: 2 tiles, 0 connections
-     * 2: 4 tiles, 4 connections
-     * 3: 2 tiles, 1 connections
+     * 1m: 4 tiles, 2 connections
+     * 2m: 2 tiles, 0 connections
+     * 3m: 4 tiles, 4 connections
+     * 4m: 2 tiles, 1 connections
      */
-    let map: TileMap = HashMap::from([
-        ([0, 0], 0),
-        ([0, 2], 0),
-        ([0, 3], 1),
-        ([1, 0], 1),
-        ([1, 1], 2),
-        ([1, 2], 2),
-        ([2, 0], 0),
-        ([2, 1], 2),
-        ([2, 2], 2),
-        ([2, 3], 3),
-        ([3, 0], 0),
-        ([3, 3], 3),
-    ]);
-    let map_size = [4, 4];
-
-    let map = remove_tiles(&map, &get_trivial_connections(&map, &map_size));
-    let mut snapshot = SichuanSolverSnapshot::try_new(&map, &map_size).unwrap();
+    let (map, _) = parse_board(
+        "
+        1m 2m 1m 1m
+        .  3m 3m .
+        1m 3m 3m .
+        2m .  4m 4m
+        ",
+    )
+    .unwrap();
+    let board = Board::from_map(&map, &[4, 4]);
+
+    let board = remove_tiles(
+        &board,
+        &get_trivial_connections(&board, &RuleSet::default()),
+    );
+    let mut snapshot =
+        SichuanSolverSnapshot::try_new(&board, &RuleSet::default(), &mut FewestRemaining).unwrap();
     assert_eq!(
         snapshot.connections,
         vec![
@@ -423,7 +1037,7 @@ fn test_snapshot() {
             [[2, 0], [3, 0]],
         ]
     );
-    snapshot.resolve();
+    snapshot.resolve(true);
 
     assert_eq!(
         snapshot.nodes(),
@@ -435,7 +1049,7 @@ fn test_snapshot() {
     );
 
     assert_eq!(snapshot.next(), Ok(()));
-    snapshot.resolve();
+    snapshot.resolve(true);
     assert_eq!(
         snapshot.nodes(),
         vec![
@@ -446,7 +1060,7 @@ fn test_snapshot() {
     );
 
     assert_eq!(snapshot.next(), Ok(()));
-    snapshot.resolve();
+    snapshot.resolve(true);
     assert_eq!(
         snapshot.nodes(),
         vec![
@@ -457,7 +1071,7 @@ fn test_snapshot() {
     );
 
     assert_eq!(snapshot.next(), Ok(()));
-    snapshot.resolve();
+    snapshot.resolve(true);
     assert_eq!(
         snapshot.nodes(),
         vec![
@@ -468,7 +1082,7 @@ fn test_snapshot() {
     );
 
     assert_eq!(snapshot.next(), Ok(()));
-    snapshot.resolve();
+    snapshot.resolve(true);
     assert_eq!(
         snapshot.nodes(),
         vec![
@@ -479,7 +1093,7 @@ fn test_snapshot() {
     );
 
     assert_eq!(snapshot.next(), Ok(()));
-    snapshot.resolve();
+    snapshot.resolve(true);
     assert_eq!(
         snapshot.nodes(),
         vec![
@@ -494,27 +1108,16 @@ fn test_snapshot() {
 
 #[test]
 fn test_sichuan_solver() {
-    /*
-     * 0 x 0 1
-     * 1 2 2 x
-     * 0 2 2 3
-     * 0 x x 3
-     */
-    let map: TileMap = HashMap::from([
-        ([0, 0], 0),
-        ([0, 2], 0),
-        ([0, 3], 1),
-        ([1, 0], 1),
-        ([1, 1], 2),
-        ([1, 2], 2),
-        ([2, 0], 0),
-        ([2, 1], 2),
-        ([2, 2], 2),
-        ([2, 3], 3),
-        ([3, 0], 0),
-        ([3, 3], 3),
-    ]);
-    let mut solver = SichuanSolver::new(&map);
+    let (map, size) = parse_board(
+        "
+        1m 2m 1m 1m
+        .  3m 3m .
+        1m 3m 3m .
+        2m .  4m 4m
+        ",
+    )
+    .unwrap();
+    let mut solver = SichuanSolver::new(&map, &size, &RuleSet::default());
 
     assert_eq!(solver.solve(), Ok(()));
 
@@ -530,3 +1133,550 @@ fn test_sichuan_solver() {
         ]
     );
 }
+
+#[test]
+fn test_sichuan_solver_path_selection() {
+    use super::utils::get_node_length;
+    let (map, size) = parse_board(
+        "
+        .  .  1m .  3m
+        .  2m 2m .  .
+        .  .  1m .  3m
+        ",
+    )
+    .unwrap();
+    let mut solver = SichuanSolver::new(&map, &size, &RuleSet::default());
+    assert_eq!(solver.solve(), Ok(()));
+    let first = solver.result();
+
+    solver.set_path_selection(PathSelection::Shortest);
+    let shortest = solver.result();
+    assert_ne!(first, shortest);
+    assert_eq!(
+        shortest,
+        vec![
+            vec![[2, 2], [3, 2]],
+            vec![[3, 1], [3, 3]],
+            vec![[5, 1], [5, 3]],
+        ]
+    );
+    for (nodes1, nodes2) in first.iter().zip(shortest.iter()) {
+        assert_eq!(get_node_edges(nodes1), get_node_edges(nodes2));
+        assert!(get_node_length(nodes2) <= get_node_length(nodes1));
+    }
+}
+
+#[test]
+fn test_sichuan_solver_rules() {
+    let (map, size) = parse_board(
+        "
+        1m 2m 1m 2m
+        ",
+    )
+    .unwrap();
+    let inside = RuleSet {
+        allow_outside: false,
+        ..RuleSet::default()
+    };
+    let straight = RuleSet {
+        max_turns: 0,
+        ..RuleSet::default()
+    };
+
+    let mut solver = SichuanSolver::new(&map, &size, &RuleSet::default());
+    assert_eq!(solver.solve(), Ok(()));
+    let mut solver = SichuanSolver::new(&map, &size, &inside);
+    assert_eq!(solver.solve(), Err(SolverError::Deadlock(vec![0, 1])));
+    /* An empty row below the tiles still carries paths. */
+    let mut solver = SichuanSolver::new(&map, &[4, 2], &inside);
+    assert_eq!(solver.solve(), Ok(()));
+    assert_eq!(solver.result()[0], vec![[0, 0], [0, 1], [2, 1], [2, 0]]);
+    let mut solver = SichuanSolver::new(&map, &size, &straight);
+    assert_eq!(solver.solve(), Err(SolverError::Deadlock(vec![0, 1])));
+
+    let (map, size) = parse_board(
+        "
+        1m 1m 2m 2m
+        ",
+    )
+    .unwrap();
+    let mut solver = SichuanSolver::new(&map, &size, &inside);
+    assert_eq!(solver.solve(), Ok(()));
+    assert_eq!(
+        solver.result(),
+        vec![vec![[0, 0], [1, 0]], vec![[2, 0], [3, 0]]]
+    );
+}
+
+#[test]
+fn test_sichuan_solver_classes() {
+    use super::classes::TileClasses;
+    let (map, size) = parse_board(
+        "
+        1m 6m 7m 1m
+        ",
+    )
+    .unwrap();
+    let inside = RuleSet {
+        allow_outside: false,
+        ..RuleSet::default()
+    };
+    let mut solver = SichuanSolver::new(&map, &size, &inside);
+    assert_eq!(solver.solve(), Err(SolverError::OddTileCount(vec![5, 6])));
+
+    let classes = RuleSet {
+        classes: TileClasses::new(&[&[5, 6]]),
+        ..inside
+    };
+    let mut solver = SichuanSolver::new(&map, &size, &classes);
+    assert_eq!(solver.solve(), Ok(()));
+    assert_eq!(
+        solver.result(),
+        vec![vec![[1, 0], [2, 0]], vec![[0, 0], [3, 0]]]
+    );
+}
+
+#[test]
+fn test_sichuan_solver_orderings() {
+    use super::ordering::MoveOrderingKind;
+    let (map, size) = parse_board(
+        "
+        1m 2m 1m 1m
+        .  3m 3m .
+        1m 3m 3m .
+        2m .  4m 4m
+        ",
+    )
+    .unwrap();
+    let rules = RuleSet::default();
+    for kind in [
+        MoveOrderingKind::FewestRemaining,
+        MoveOrderingKind::FewestBlocking,
+        MoveOrderingKind::ShortestPath,
+        MoveOrderingKind::Random(0),
+        MoveOrderingKind::Random(1),
+    ] {
+        let mut solver = SichuanSolver::with_ordering(&map, &size, &rules, kind.create());
+        assert_eq!(solver.solve(), Ok(()));
+
+        let states = get_board_states(&map, &size, &solver.result(), &rules);
+        assert!(states.last().unwrap().is_empty());
+    }
+}
+
+#[test]
+fn test_sichuan_solver_progress_observer() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    let (map, size) = parse_board(
+        "
+        1m 2m 1m 2m
+        2m 1m 2m 1m
+        ",
+    )
+    .unwrap();
+    let reports: Rc<RefCell<Vec<SolverProgressReport>>> = Rc::new(RefCell::new(Vec::new()));
+    let mut solver = SichuanSolver::new(&map, &size, &RuleSet::default());
+    let reports_clone = reports.clone();
+    solver.set_progress_observer(
+        Box::new(move |report: &SolverProgressReport| {
+            reports_clone.borrow_mut().push(report.clone())
+        }),
+        1,
+    );
+    assert_eq!(solver.solve(), Ok(()));
+
+    let reports = reports.borrow();
+    assert!(!reports.is_empty());
+    for (i, report) in reports.iter().enumerate() {
+        assert_eq!(report.n_visited, i + 1);
+        assert_eq!(report.n_tiles, 8);
+        assert!(report.n_best_remaining <= report.n_remaining);
+        assert!(report.cursors.iter().all(|&[cursor, n]| cursor < n));
+    }
+
+    let progress = solver.progress();
+    assert_eq!(progress.n_remaining, 0);
+    assert_eq!(progress.n_best_remaining, 0);
+}
+
+#[test]
+fn test_sichuan_solver_gravity() {
+    let (map, size) = parse_board(
+        "
+        2m 3m 4m
+        3m 2m 4m
+        1m .  1m
+        ",
+    )
+    .unwrap();
+    let gravity = RuleSet {
+        gravity: true,
+        ..RuleSet::default()
+    };
+
+    let mut solver = SichuanSolver::new(&map, &size, &RuleSet::default());
+    assert!(solver.solve().is_err());
+
+    let mut solver = SichuanSolver::new(&map, &size, &gravity);
+    assert_eq!(solver.solve(), Ok(()));
+    let result = solver.result();
+    assert_eq!(
+        result,
+        vec![
+            vec![[1, 3], [3, 3]],
+            vec![[1, 2], [2, 2]],
+            vec![[1, 3], [2, 3], [2, 2]],
+            vec![[3, 2], [3, 3]],
+        ]
+    );
+
+    let states = get_board_states(&map, &size, &result, &gravity);
+    assert_eq!(states.len(), 5);
+    assert_eq!(states[0], RuleSet::default().pad_map(&map));
+    assert_eq!(
+        states[1],
+        HashMap::from([
+            ([1, 2], 1),
+            ([1, 3], 2),
+            ([2, 1], 2),
+            ([2, 2], 1),
+            ([3, 2], 3),
+            ([3, 3], 3),
+        ])
+    );
+    assert!(states[4].is_empty());
+    assert_eq!(
+        get_step_tiles(&states[0], &states, &result),
+        vec![Some(0), Some(1), Some(2), Some(3)]
+    );
+    assert_eq!(
+        get_step_tiles(&states[0], &[], &result),
+        vec![Some(0), Some(2), Some(0), Some(3)]
+    );
+
+    let moves: Vec<[Coord; 2]> = result
+        .iter()
+        .map(|node| get_node_edges(node).map(|[x, y]| [x - 1, y - 1]))
+        .collect();
+    assert_eq!(
+        route_moves(&map, &size, &moves, &gravity, PathSelection::First),
+        Some(result)
+    );
+    assert_eq!(
+        route_moves(&map, &size, &moves[1..], &gravity, PathSelection::First),
+        None
+    );
+}
+
+#[test]
+fn test_sichuan_solver_errors() {
+    let (map, size) = parse_board(
+        "
+        2m 3m 1m
+        3m 1m 2m
+        ",
+    )
+    .unwrap();
+    let mut solver = SichuanSolver::new(&map, &size, &RuleSet::default());
+    assert_eq!(
+        solver.solve(),
+        Err(SolverError::NoLegalFirstMove(SolverDiagnostics {
+            deepest: Vec::new(),
+            remaining: super::utils::pad_tilemap(&map),
+        }))
+    );
+
+    let (map, size) = parse_board(
+        "
+        2m 3m 1m .  4m
+        3m 1m 2m .  4m
+        ",
+    )
+    .unwrap();
+    let mut solver = SichuanSolver::new(&map, &size, &RuleSet::default());
+    assert_eq!(
+        solver.solve(),
+        Err(SolverError::SearchExhausted(SolverDiagnostics {
+            deepest: vec![vec![[5, 1], [5, 2]]],
+            remaining: HashMap::from([
+                ([1, 1], 1),
+                ([1, 2], 2),
+                ([2, 1], 2),
+                ([2, 2], 0),
+                ([3, 1], 0),
+                ([3, 2], 1),
+            ]),
+        }))
+    );
+
+    let (map, size) = parse_board(
+        "
+        1m 2m .  3m
+        2m 1m .  3m
+        ",
+    )
+    .unwrap();
+    let mut solver = SichuanSolver::new(&map, &size, &RuleSet::default());
+    assert_eq!(solver.solve(), Err(SolverError::Deadlock(vec![0, 1])));
+
+    let (map, size) = parse_board(
+        "
+        1m 2m
+        2m .
+        ",
+    )
+    .unwrap();
+    let mut solver = SichuanSolver::new(&map, &size, &RuleSet::default());
+    assert_eq!(solver.solve(), Err(SolverError::OddTileCount(vec![0])));
+}
+
+#[test]
+fn test_sichuan_solver_budget() {
+    let (map, size) = parse_board(
+        "
+        1m 2m 1m 1m
+        .  3m 3m .
+        1m 3m 3m .
+        2m .  4m 4m
+        ",
+    )
+    .unwrap();
+    let mut solver = SichuanSolver::new(&map, &size, &RuleSet::default());
+
+    solver.set_max_steps(Some(1));
+    match solver.solve() {
+        Err(SolverError::BudgetExceeded(diagnostics)) => {
+            assert_eq!(
+                remove_tiles(&solver.initial_map, &diagnostics.deepest).to_map(),
+                diagnostics.remaining
+            );
+        }
+        _ => panic!(),
+    }
+    solver.set_max_steps(None);
+    assert_eq!(solver.solve(), Ok(()));
+}
+
+#[test]
+fn test_sichuan_solver_solve_for() {
+    let (map, size) = parse_board(
+        "
+        1m 2m 1m 1m
+        .  3m 3m .
+        1m 3m 3m .
+        2m .  4m 4m
+        ",
+    )
+    .unwrap();
+    let mut solver = SichuanSolver::new(&map, &size, &RuleSet::default());
+
+    assert_eq!(solver.solve_for(0), SolverProgress::Pending);
+    assert_eq!(solver.solve_for(1), SolverProgress::Pending);
+    let progress = loop {
+        match solver.solve_for(1) {
+            SolverProgress::Pending => continue,
+            progress => break progress,
+        }
+    };
+    assert_eq!(progress, SolverProgress::Solved);
+    assert_eq!(solver.solve_for(1), SolverProgress::Solved);
+
+    let mut expected = SichuanSolver::new(&map, &size, &RuleSet::default());
+    assert_eq!(expected.solve(), Ok(()));
+    assert_eq!(solver.result(), expected.result());
+}
+
+#[test]
+fn test_get_commutation_key() {
+    let nodes1: Vec<Nodes> = vec![
+        vec![[0, 0], [0, 1]],
+        vec![[2, 0], [2, 1]],
+        vec![[1, 0], [0, 0], [0, 1], [1, 1]],
+    ];
+    let nodes2: Vec<Nodes> = vec![
+        vec![[2, 1], [2, 0]],
+        vec![[0, 0], [0, 1]],
+        vec![[1, 0], [0, 0], [0, 1], [1, 1]],
+    ];
+    let nodes3: Vec<Nodes> = vec![
+        vec![[0, 0], [0, 1]],
+        vec![[1, 0], [0, 0], [0, 1], [1, 1]],
+        vec![[2, 0], [2, 1]],
+    ];
+    let expected = vec![
+        vec![[[0, 0], [0, 1]], [[2, 0], [2, 1]]],
+        vec![[[1, 0], [1, 1]]],
+    ];
+
+    assert_eq!(get_commutation_key(&nodes1), expected);
+    assert_eq!(get_commutation_key(&nodes2), expected);
+    assert_eq!(get_commutation_key(&nodes3), expected);
+    assert_eq!(
+        get_commutation_key(&[nodes1[2].clone(), nodes1[0].clone()]),
+        vec![vec![[[1, 0], [1, 1]]], vec![[[0, 0], [0, 1]]]]
+    );
+}
+
+#[test]
+fn test_sichuan_solver_solutions() {
+    let (map, size) = parse_board(
+        "
+        1m 2m 1m 1m
+        .  3m 3m .
+        1m 3m 3m .
+        2m .  4m 4m
+        ",
+    )
+    .unwrap();
+
+    let mut first = SichuanSolver::new(&map, &size, &RuleSet::default());
+    assert_eq!(first.solve(), Ok(()));
+
+    let mut solver = SichuanSolver::new(&map, &size, &RuleSet::default());
+    let solutions: Vec<Vec<Nodes>> = solver
+        .solutions(SolutionOptions::default())
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert!(solutions.len() > 1);
+    assert!(solutions.contains(&first.result()));
+    for (i, solution) in solutions.iter().enumerate() {
+        assert!(remove_tiles(&solver.initial_map, solution).is_empty());
+        assert!(solutions[..i].iter().all(|other| other != solution));
+    }
+
+    let mut solver = SichuanSolver::new(&map, &size, &RuleSet::default());
+    let options = SolutionOptions {
+        limit: Some(2),
+        ..SolutionOptions::default()
+    };
+    assert_eq!(solver.solutions(options).count(), 2);
+
+    let mut solver = SichuanSolver::new(&map, &size, &RuleSet::default());
+    let options = SolutionOptions {
+        skip_commuting: true,
+        ..SolutionOptions::default()
+    };
+    let distinct: Vec<Vec<Nodes>> = solver.solutions(options).collect::<Result<_, _>>().unwrap();
+    assert!(distinct.len() < solutions.len());
+    for (i, solution) in distinct.iter().enumerate() {
+        let key = get_commutation_key(solution);
+        assert!(distinct[..i]
+            .iter()
+            .all(|other| get_commutation_key(other) != key));
+    }
+
+    /* Both pairs are forced, yet either may go first. */
+    let (map, size) = parse_board(
+        "
+        1m 1m 2m 2m
+        ",
+    )
+    .unwrap();
+    let mut solver = SichuanSolver::new(&map, &size, &RuleSet::default());
+    assert_eq!(solver.solutions(SolutionOptions::default()).count(), 2);
+    let mut solver = SichuanSolver::new(&map, &size, &RuleSet::default());
+    let options = SolutionOptions {
+        skip_commuting: true,
+        ..SolutionOptions::default()
+    };
+    assert_eq!(solver.solutions(options).count(), 1);
+
+    let mut solver = SichuanSolver::new(&map, &size, &RuleSet::default());
+    solver.set_max_steps(Some(1));
+    let mut solutions = solver.solutions(SolutionOptions::default());
+    assert!(matches!(
+        solutions.next(),
+        Some(Err(SolverError::BudgetExceeded(_)))
+    ));
+    assert_eq!(solutions.next(), None);
+
+    let (map, size) = parse_board(
+        "
+        1m 2m
+        2m 1m
+        ",
+    )
+    .unwrap();
+    let inside = RuleSet {
+        allow_outside: false,
+        ..RuleSet::default()
+    };
+    let mut solver = SichuanSolver::new(&map, &size, &inside);
+    assert_eq!(
+        solver.solutions(SolutionOptions::default()).next(),
+        Some(Err(SolverError::Deadlock(vec![0, 1])))
+    );
+}
+
+#[test]
+fn test_sichuan_solver_solutions_gravity() {
+    /* Pairs whose paths do not touch still move other tiles under gravity. */
+    let (map, size) = parse_board(
+        "
+        1m 3m .
+        3m .  2m
+        1m 2m .
+        ",
+    )
+    .unwrap();
+    let gravity = RuleSet {
+        gravity: true,
+        ..RuleSet::default()
+    };
+    let mut solver = SichuanSolver::new(&map, &size, &gravity);
+    let all = solver.solutions(SolutionOptions::default()).count();
+    let mut solver = SichuanSolver::new(&map, &size, &gravity);
+    let options = SolutionOptions {
+        skip_commuting: true,
+        ..SolutionOptions::default()
+    };
+    assert_eq!(all, 6);
+    assert_eq!(solver.solutions(options).count(), all);
+}
+
+#[test]
+fn test_sichuan_solver_dead_states() {
+    let (map, size) = parse_board(
+        "
+        2m 3m 1m .  4m 4m
+        3m 1m 2m .  4m 4m
+        ",
+    )
+    .unwrap();
+    let (dead_end, _) = parse_board(
+        "
+        .  .  .  .
+        .  2m 3m 1m
+        .  3m 1m 2m
+        ",
+    )
+    .unwrap();
+    let map_size = [8, 4];
+
+    let mut solver = SichuanSolver::new(&map, &size, &RuleSet::default());
+    assert!(matches!(
+        solver.solve(),
+        Err(SolverError::SearchExhausted(_))
+    ));
+    assert!(solver
+        .dead_states
+        .contains(&Board::from_map(&dead_end, &map_size).key()));
+    assert!(solver
+        .dead_states
+        .contains(&RuleSet::default().create_board(&map, &size).key()));
+}
+
+#[test]
+fn test_sichuan_solver_deadlock_pruning() {
+    let (map, size) = parse_board(
+        "
+        1m 2m .  3m 3m
+        2m 1m .  3m 3m
+        ",
+    )
+    .unwrap();
+    let mut solver = SichuanSolver::new(&map, &size, &RuleSet::default());
+    assert_eq!(solver.solve(), Err(SolverError::Deadlock(vec![0, 1])));
+    assert!(solver.snapshots.is_empty());
+}
@@ -1,4 +1,4 @@
-use super::super::components::{Coord, Nodes, TileMap};
+use super::super::components::{Coord, Nodes, Tile, TileMap};
 use std::cmp::max;
 
 pub fn pad_tilemap(map: &TileMap) -> TileMap {
@@ -15,6 +15,75 @@ pub fn get_node_edges(node: &Nodes) -> [Coord; 2] {
     [node.first().unwrap().clone(), node.last().unwrap().clone()]
 }
 
+pub fn get_node_coords(node: &Nodes) -> Vec<Coord> {
+    let mut coords: Vec<Coord> = node.first().into_iter().cloned().collect();
+    for segment in node.windows(2) {
+        let (&[x1, y1], &[x2, y2]) = (&segment[0], &segment[1]);
+        let path: Vec<Coord> = match x1 == x2 {
+            true if y1 < y2 => ((y1 + 1)..=y2).map(|y| [x1, y]).collect(),
+            true => (y2..y1).rev().map(|y| [x1, y]).collect(),
+            false if x1 < x2 => ((x1 + 1)..=x2).map(|x| [x, y1]).collect(),
+            false => (x2..x1).rev().map(|x| [x, y1]).collect(),
+        };
+        coords.extend(path);
+    }
+    coords
+}
+
+pub fn get_node_length(node: &Nodes) -> usize {
+    node.windows(2)
+        .map(|segment| {
+            segment[0][0].abs_diff(segment[1][0]) + segment[0][1].abs_diff(segment[1][1])
+        })
+        .sum()
+}
+
+pub fn splitmix64(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+pub fn get_zobrist_key(&[x, y]: &Coord, tile: Tile) -> u64 {
+    splitmix64(((x as u64) << 40) ^ ((y as u64) << 16) ^ (tile as u64))
+}
+
+pub fn get_map_hash(map: &TileMap) -> u64 {
+    map.iter().fold(0, |hash, (coord, &tile)| {
+        hash ^ get_zobrist_key(coord, tile)
+    })
+}
+
+pub struct SeededRng {
+    seed: u64,
+    counter: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed: splitmix64(seed),
+            counter: 0,
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.counter += 1;
+        splitmix64(self.seed ^ self.counter)
+    }
+
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
 #[test]
 fn test_pad_tilemap() {
     use std::collections::HashMap;
@@ -37,6 +106,15 @@ fn test_pad_tilemap() {
     assert_eq!(expected, pad_tilemap(&map));
 }
 
+#[test]
+fn test_get_node_coords() {
+    assert_eq!(get_node_coords(&vec![[0, 0], [0, 1]]), vec![[0, 0], [0, 1]]);
+    assert_eq!(
+        get_node_coords(&vec![[2, 1], [0, 1], [0, 3], [1, 3]]),
+        vec![[2, 1], [1, 1], [0, 1], [0, 2], [0, 3], [1, 3]]
+    );
+}
+
 #[test]
 fn test_get_map_size() {
     use std::collections::HashMap;
@@ -49,3 +127,41 @@ fn test_get_map_size() {
     let expected = [3, 4];
     assert_eq!(get_size_from_map(&map), expected);
 }
+
+#[test]
+fn test_get_node_length() {
+    assert_eq!(get_node_length(&vec![[0, 0], [0, 1]]), 1);
+    assert_eq!(get_node_length(&vec![[0, 3], [2, 3], [2, 0]]), 5);
+    assert_eq!(get_node_length(&vec![[3, 1], [0, 1], [0, 2], [1, 2]]), 5);
+}
+
+#[test]
+fn test_get_map_hash() {
+    use std::collections::HashMap;
+    let map1: TileMap = HashMap::from([([0, 0], 0), ([0, 1], 1), ([1, 0], 1), ([1, 1], 0)]);
+    let map2: TileMap = HashMap::from([([1, 1], 0), ([1, 0], 1), ([0, 1], 1), ([0, 0], 0)]);
+    let map3: TileMap = HashMap::from([([0, 0], 1), ([0, 1], 0), ([1, 0], 0), ([1, 1], 1)]);
+    let map4: TileMap = HashMap::from([([0, 1], 1), ([1, 0], 1)]);
+
+    assert_eq!(get_map_hash(&map1), get_map_hash(&map2));
+    assert_ne!(get_map_hash(&map1), get_map_hash(&map3));
+    assert_eq!(
+        get_map_hash(&map1) ^ get_zobrist_key(&[0, 0], 0) ^ get_zobrist_key(&[1, 1], 0),
+        get_map_hash(&map4)
+    );
+    assert_eq!(get_map_hash(&HashMap::new()), 0);
+}
+
+#[test]
+fn test_seeded_rng() {
+    let mut rng1 = SeededRng::new(7);
+    let mut rng2 = SeededRng::new(7);
+    let values1: Vec<u64> = (0..4).map(|_| rng1.next_u64()).collect();
+    let values2: Vec<u64> = (0..4).map(|_| rng2.next_u64()).collect();
+    assert_eq!(values1, values2);
+
+    let mut items: Vec<usize> = (0..10).collect();
+    rng1.shuffle(&mut items);
+    items.sort();
+    assert_eq!(items, (0..10).collect::<Vec<usize>>());
+}
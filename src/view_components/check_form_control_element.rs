@@ -0,0 +1,42 @@
+use wasm_bindgen::JsCast;
+use web_sys::Event;
+use web_sys::HtmlInputElement;
+use yew::callback::Callback;
+use yew::prelude::*;
+use yew::virtual_dom::AttrValue;
+use yew::Properties;
+
+#[derive(Properties, PartialEq)]
+pub struct CheckFormControlElementProps {
+    pub label: AttrValue,
+    pub checked: bool,
+    #[prop_or(false)]
+    pub disabled: bool,
+
+    pub onchange: Callback<bool>,
+}
+
+#[function_component(CheckFormControlElement)]
+pub fn check_form_control_element(props: &CheckFormControlElementProps) -> Html {
+    let onchange = props.onchange.clone();
+
+    html! {
+        <div class="form-check">
+            <input
+                class="form-check-input"
+                type="checkbox"
+                checked={props.checked}
+                disabled={props.disabled}
+                onchange={Callback::from(move |e: Event| {
+                    let target = e.target().and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+                    if let Some(input) = target {
+                        onchange.emit(input.checked());
+                    }
+                })}
+            />
+            <label class="form-check-label">
+                {&props.label}
+            </label>
+        </div>
+    }
+}
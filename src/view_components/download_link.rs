@@ -0,0 +1,31 @@
+use yew::prelude::*;
+use yew::virtual_dom::AttrValue;
+use yew::Properties;
+
+#[derive(Properties, PartialEq)]
+pub struct DownloadLinkProps {
+    pub label: AttrValue,
+    pub filename: AttrValue,
+    pub content: AttrValue,
+    #[prop_or(AttrValue::from("application/json"))]
+    pub mime_type: AttrValue,
+}
+
+#[function_component(DownloadLink)]
+pub fn download_link(props: &DownloadLinkProps) -> Html {
+    let href = format!(
+        "data:{};charset=utf-8,{}",
+        props.mime_type,
+        String::from(js_sys::encode_uri_component(&props.content)),
+    );
+
+    html! {
+        <a
+            class="btn btn-outline-secondary"
+            href={href}
+            download={props.filename.clone()}
+        >
+            {&props.label}
+        </a>
+    }
+}
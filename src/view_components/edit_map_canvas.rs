@@ -1,15 +1,25 @@
+use std::collections::HashMap;
+
 use yew::callback::Callback;
 use yew::prelude::*;
+use yew::virtual_dom::AttrValue;
 use yew::Properties;
 
 use super::super::components::{Coord, TileMap};
 use super::tile_map_canvas::TileMapCanvas;
 
+const HINT_TILE_COLOR: &str = "lightgreen";
+const UNCERTAIN_TILE_COLOR: &str = "khaki";
+
 #[derive(Properties, PartialEq)]
 pub struct EditMapCanvasProps {
     pub rows: usize,
     pub cols: usize,
     pub tiles: TileMap,
+    #[prop_or(Vec::new())]
+    pub hint: Vec<Coord>,
+    #[prop_or(Vec::new())]
+    pub uncertain: Vec<Coord>,
 
     pub onselect: Callback<Coord>,
 }
@@ -17,12 +27,24 @@ pub struct EditMapCanvasProps {
 #[function_component(EditMapCanvas)]
 pub fn edit_map_canvas(props: &EditMapCanvasProps) -> Html {
     let onselect = props.onselect.clone();
+    let backgrounds: HashMap<Coord, AttrValue> = props
+        .uncertain
+        .iter()
+        .map(|&coord| (coord, AttrValue::from(UNCERTAIN_TILE_COLOR)))
+        .chain(
+            props
+                .hint
+                .iter()
+                .map(|&coord| (coord, AttrValue::from(HINT_TILE_COLOR))),
+        )
+        .collect();
     html! {
         <div>
             <TileMapCanvas
                 rows={props.rows}
                 cols={props.cols}
                 tiles={props.tiles.to_owned()}
+                bg_color={backgrounds}
                 onclick={onselect}
             />
         </div>
@@ -1,20 +1,55 @@
 use std::collections::HashMap;
 
+use canvas::worker::{
+    SolveRequest, SolverOutput, SolverRequest, SolverResponse, SolverWorker, SOLVER_WORKER_PATH,
+};
+use gloo_worker::{Spawnable, WorkerBridge};
 use yew::callback::Callback;
 use yew::prelude::*;
+use yew::virtual_dom::AttrValue;
 use yew::Properties;
 
-use super::super::components::{Coord, Tile};
+use super::super::archive::BoardDocument;
+use super::super::catalogue::{get_tiles_of_suit, Suit, SUITS};
+use super::super::components::{Coord, Tile, WALL};
+use super::super::notation::{format_board, parse_board};
+use super::super::recognition::Recognition;
+use super::super::share::SharedBoard;
+use super::super::solver::classes::TileClasses;
+use super::super::solver::connect::PathSelection;
+use super::super::solver::deadlock::detect_deadlock;
+use super::super::solver::generator::generate_board;
+use super::super::solver::hint::get_first_move;
+use super::super::solver::ordering::MoveOrderingKind;
+use super::super::solver::progress::SolverProgressReport;
+use super::super::solver::rules::RuleSet;
+use super::super::solver::solver::SolverError;
+use super::super::solver::utils::get_size_from_map;
 use super::card::Card;
 use super::edit_map_canvas::EditMapCanvas;
 use super::edit_view_control::EditViewControl;
 
+const HINT_MAX_STEPS: usize = 100_000;
+
 pub enum EditViewMsg {
     RowsChanged(usize),
     ColsChanged(usize),
+    RulesChanged(RuleSet),
+    OrderingChanged(MoveOrderingKind),
     TileChanged(Option<Tile>),
     MapUpdated(Coord),
     MapSubmitted,
+    SeedChanged(u64),
+    MapGenerated,
+    HintRequested,
+    HintResponded(SolverResponse),
+    TextChanged(String),
+    MapImported,
+    MapExported,
+    BoardLoaded(String),
+    ScreenshotRecognized(Result<Recognition, String>),
+    MapCleared,
+    SolverCancelled,
 }
 
 #[derive(Properties, PartialEq)]
@@ -23,15 +58,44 @@ pub struct EditViewProps {
     pub cols: usize,
 
     pub tiles: HashMap<Coord, Tile>,
+    #[prop_or_default]
+    pub rules: RuleSet,
+    #[prop_or_default]
+    pub ordering: MoveOrderingKind,
+    #[prop_or(false)]
+    pub solving: bool,
+    #[prop_or(None)]
+    pub progress: Option<SolverProgressReport>,
 
     pub on_rows_change: Callback<usize>,
     pub on_cols_change: Callback<usize>,
+    pub on_rules_change: Callback<RuleSet>,
+    pub on_ordering_change: Callback<MoveOrderingKind>,
+    pub on_tiles_change: Callback<HashMap<Coord, Tile>>,
     pub onsubmit: Callback<HashMap<Coord, Tile>>,
+    pub onclear: Callback<()>,
+    pub oncancel: Callback<()>,
 }
 
 pub struct EditView {
     pub current: Option<Tile>,
     pub tiles: HashMap<Coord, Tile>,
+    pub hint: Option<Result<Option<[Coord; 2]>, SolverError>>,
+    // Hints are searched in a worker of their own, so a solve is not cancelled.
+    pub hint_solver: WorkerBridge<SolverWorker>,
+    pub hint_generation: usize,
+    pub hinting: bool,
+    pub seed: u64,
+    pub text: Option<String>,
+    pub import_failure: Option<String>,
+    pub uncertain: Vec<Coord>,
+    // Derived from the tiles, the board size and the rules, see `refresh`.
+    pub board: (usize, usize, RuleSet),
+    pub submittable: bool,
+    pub deadlock: Option<Vec<Tile>>,
+    pub board_text: String,
+    pub board_json: String,
+    pub share_fragment: String,
 }
 
 impl Component for EditView {
@@ -39,10 +103,40 @@ impl Component for EditView {
     type Properties = EditViewProps;
 
     fn create(ctx: &Context<Self>) -> Self {
-        Self {
+        let link = ctx.link().clone();
+        let hint_solver = SolverWorker::spawner()
+            .callback(move |response| link.send_message(EditViewMsg::HintResponded(response)))
+            .spawn(SOLVER_WORKER_PATH);
+
+        let mut edit_view = Self {
             current: None,
             tiles: ctx.props().tiles.clone(),
+            hint: None,
+            hint_solver,
+            hint_generation: 0,
+            hinting: false,
+            seed: 0,
+            text: None,
+            import_failure: None,
+            uncertain: Vec::new(),
+            board: (ctx.props().rows, ctx.props().cols, ctx.props().rules),
+            submittable: false,
+            deadlock: None,
+            board_text: String::new(),
+            board_json: String::new(),
+            share_fragment: String::new(),
+        };
+        edit_view.refresh(ctx.props());
+        edit_view
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>) -> bool {
+        /* The tiles are kept here, so only the size and the rules come in. */
+        let props = ctx.props();
+        if self.board != (props.rows, props.cols, props.rules) {
+            self.refresh(props);
         }
+        true
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
@@ -55,11 +149,23 @@ impl Component for EditView {
                 ctx.props().on_cols_change.emit(rows);
                 false
             }
+            Self::Message::RulesChanged(rules) => {
+                self.clear_hint();
+                ctx.props().on_rules_change.emit(rules);
+                true
+            }
+            Self::Message::OrderingChanged(ordering) => {
+                ctx.props().on_ordering_change.emit(ordering);
+                false
+            }
             Self::Message::TileChanged(tile) => {
                 self.current = tile;
                 true
             }
             Self::Message::MapUpdated(coord) => {
+                self.clear_hint();
+                self.text = None;
+                self.uncertain.retain(|&c| c != coord);
                 match (self.current, self.tiles.get(&coord)) {
                     (Some(next), Some(&current)) if next == current => {
                         self.tiles.remove(&coord);
@@ -71,16 +177,162 @@ impl Component for EditView {
                         self.tiles.remove(&coord);
                     }
                 }
+                self.refresh(ctx.props());
+                ctx.props().on_tiles_change.emit(self.tiles.to_owned());
                 true
             }
             Self::Message::MapSubmitted => {
                 ctx.props().onsubmit.emit(self.tiles.to_owned());
                 false
             }
+            Self::Message::SeedChanged(seed) => {
+                self.seed = seed;
+                true
+            }
+            Self::Message::MapGenerated => {
+                let tiles: Vec<Tile> = SUITS
+                    .iter()
+                    .filter(|&&suit| suit != Suit::Flowers && suit != Suit::Seasons)
+                    .flat_map(|&suit| get_tiles_of_suit(suit))
+                    .collect();
+                let (rows, cols) = (ctx.props().rows, ctx.props().cols);
+                if let Some(map) = generate_board(rows, cols, &tiles, &ctx.props().rules, self.seed)
+                {
+                    self.tiles = map;
+                    self.uncertain.clear();
+                    self.clear_hint();
+                    self.text = None;
+                    self.refresh(ctx.props());
+                    ctx.props().on_tiles_change.emit(self.tiles.to_owned());
+                }
+                true
+            }
+            Self::Message::HintRequested => {
+                self.clear_hint();
+                self.hint_generation += 1;
+                self.hint_solver
+                    .send(SolverRequest::Solve(Box::new(SolveRequest {
+                        generation: self.hint_generation,
+                        map: self.tiles.clone(),
+                        size: [ctx.props().cols, ctx.props().rows],
+                        rules: ctx.props().rules,
+                        path_selection: PathSelection::First,
+                        ordering: ctx.props().ordering,
+                        max_steps: Some(HINT_MAX_STEPS),
+                    })));
+                self.hinting = true;
+                true
+            }
+            Self::Message::HintResponded(response) => {
+                if !self.hinting || response.generation != self.hint_generation {
+                    return false;
+                }
+                match response.output {
+                    SolverOutput::Progress(_) => false,
+                    SolverOutput::Finished(request, result) => {
+                        self.hint =
+                            Some(result.map(|nodes| get_first_move(&nodes, &request.rules)));
+                        self.hinting = false;
+                        true
+                    }
+                }
+            }
+            Self::Message::TextChanged(text) => {
+                self.text = Some(text);
+                true
+            }
+            Self::Message::MapImported => {
+                let text = match &self.text {
+                    Some(text) => text,
+                    None => return false,
+                };
+                match parse_board(text) {
+                    Ok((map, [cols, rows])) => {
+                        if rows > 0 && cols > 0 {
+                            ctx.props().on_rows_change.emit(rows);
+                            ctx.props().on_cols_change.emit(cols);
+                        }
+                        self.tiles = map;
+                        self.uncertain.clear();
+                        self.clear_hint();
+                        self.text = None;
+                        self.import_failure = None;
+                        self.refresh(ctx.props());
+                        ctx.props().on_tiles_change.emit(self.tiles.to_owned());
+                    }
+                    Err(err) => {
+                        self.import_failure = Some(err.to_string());
+                    }
+                }
+                true
+            }
+            Self::Message::MapExported => {
+                self.text = None;
+                self.import_failure = None;
+                true
+            }
+            Self::Message::BoardLoaded(json) => {
+                match BoardDocument::from_json(&json) {
+                    Ok(board) => {
+                        ctx.props().on_rows_change.emit(board.rows);
+                        ctx.props().on_cols_change.emit(board.cols);
+                        self.tiles = board.to_map();
+                        self.uncertain.clear();
+                        self.clear_hint();
+                        self.text = None;
+                        self.import_failure = None;
+                        self.refresh(ctx.props());
+                        ctx.props().on_tiles_change.emit(self.tiles.to_owned());
+                    }
+                    Err(err) => {
+                        self.import_failure = Some(err.to_string());
+                    }
+                }
+                true
+            }
+            Self::Message::ScreenshotRecognized(result) => {
+                match result {
+                    Ok(recognition) => {
+                        ctx.props().on_rows_change.emit(recognition.grid.rows);
+                        ctx.props().on_cols_change.emit(recognition.grid.cols);
+                        self.tiles = recognition.tiles;
+                        self.uncertain = recognition.uncertain;
+                        self.clear_hint();
+                        self.text = None;
+                        self.import_failure = None;
+                        self.refresh(ctx.props());
+                        ctx.props().on_tiles_change.emit(self.tiles.to_owned());
+                    }
+                    Err(err) => {
+                        self.import_failure = Some(err);
+                    }
+                }
+                true
+            }
+            Self::Message::MapCleared => {
+                self.tiles.clear();
+                self.uncertain.clear();
+                self.clear_hint();
+                self.text = None;
+                self.import_failure = None;
+                self.refresh(ctx.props());
+                ctx.props().onclear.emit(());
+                true
+            }
+            Self::Message::SolverCancelled => {
+                ctx.props().oncancel.emit(());
+                false
+            }
         }
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
+        let (hint, hint_failure) = match &self.hint {
+            Some(Ok(Some(pair))) => (pair.to_vec(), None),
+            Some(Err(err)) => (Vec::new(), Some(err.clone())),
+            _ => (Vec::new(), None),
+        };
+
         html! {
             <div class="row">
                 <div class="col-12 col-lg-4">
@@ -89,11 +341,36 @@ impl Component for EditView {
                             rows={ctx.props().rows}
                             cols={ctx.props().cols}
                             tile={self.current}
-                            submittable={validate_tiles(&self.tiles)}
+                            rules={ctx.props().rules}
+                            ordering={ctx.props().ordering}
+                            submittable={self.submittable}
+                            deadlock={self.deadlock.clone()}
+                            hint_failure={hint_failure}
+                            hinting={self.hinting}
+                            seed={self.seed}
+                            text={self.text.clone().unwrap_or_else(|| self.board_text.clone())}
+                            import_failure={self.import_failure.clone()}
+                            board_json={self.board_json.clone()}
+                            share_fragment={self.share_fragment.clone()}
+                            n_uncertain={self.uncertain.len()}
+                            solving={ctx.props().solving}
+                            progress={ctx.props().progress.clone()}
                             on_rows_change={ctx.link().callback(|rows| Self::Message::RowsChanged(rows))}
                             on_cols_change={ctx.link().callback(|cols| Self::Message::ColsChanged(cols))}
                             on_tile_change={ctx.link().callback(|tile| Self::Message::TileChanged(tile))}
+                            on_rules_change={ctx.link().callback(|rules| Self::Message::RulesChanged(rules))}
+                            on_ordering_change={ctx.link().callback(|ordering| Self::Message::OrderingChanged(ordering))}
                             onsubmit={ctx.link().callback(|_| Self::Message::MapSubmitted)}
+                            onhint={ctx.link().callback(|_| Self::Message::HintRequested)}
+                            on_seed_change={ctx.link().callback(|seed| Self::Message::SeedChanged(seed))}
+                            on_text_change={ctx.link().callback(|text: AttrValue| Self::Message::TextChanged(text.to_string()))}
+                            onimport={ctx.link().callback(|_| Self::Message::MapImported)}
+                            onexport={ctx.link().callback(|_| Self::Message::MapExported)}
+                            on_board_load={ctx.link().callback(|json| Self::Message::BoardLoaded(json))}
+                            on_screenshot_load={ctx.link().callback(|result| Self::Message::ScreenshotRecognized(result))}
+                            onclear={ctx.link().callback(|_| Self::Message::MapCleared)}
+                            ongenerate={ctx.link().callback(|_| Self::Message::MapGenerated)}
+                            oncancel={ctx.link().callback(|_| Self::Message::SolverCancelled)}
                         />
                     </Card>
                 </div>
@@ -103,6 +380,8 @@ impl Component for EditView {
                             rows={ctx.props().rows}
                             cols={ctx.props().cols}
                             tiles={self.tiles.to_owned()}
+                            hint={hint}
+                            uncertain={self.uncertain.clone()}
                             onselect={ctx.link().callback(|coord| Self::Message::MapUpdated(coord))}
                         />
                     </Card>
@@ -112,12 +391,56 @@ impl Component for EditView {
     }
 }
 
-fn validate_tiles(tiles: &HashMap<Coord, Tile>) -> bool {
-    let mut has_even_tiles: HashMap<&Tile, bool> = HashMap::new();
-    for tile in tiles.values() {
-        match has_even_tiles.contains_key(tile) {
-            true => has_even_tiles.insert(tile, !has_even_tiles[tile]),
-            false => has_even_tiles.insert(tile, false),
+impl EditView {
+    fn refresh(&mut self, props: &EditViewProps) {
+        /*
+         * The deadlock scan and the exports are only redone when the tiles,
+         * the board size or the rules change, not on every render.
+         */
+        let (rows, cols, rules) = (props.rows, props.cols, props.rules);
+        self.board = (rows, cols, rules);
+        self.submittable = validate_tiles(&self.tiles, &rules.classes);
+        self.deadlock = match self.submittable {
+            true => detect_deadlock(&self.tiles, &[cols, rows], &rules),
+            false => None,
+        };
+        /* Tiles left outside after shrinking the board are still exported. */
+        let [map_cols, map_rows] = get_size_from_map(&self.tiles);
+        self.board_text = format_board(&self.tiles, &[cols.max(map_cols), rows.max(map_rows)])
+            .unwrap_or_default();
+        self.board_json = BoardDocument::new(rows, cols, &self.tiles).to_json();
+        self.share_fragment = SharedBoard {
+            rows,
+            cols,
+            tiles: self.tiles.clone(),
+            rules,
+            step: None,
+            moves: Vec::new(),
+        }
+        .to_fragment()
+        .unwrap_or_default();
+    }
+
+    fn clear_hint(&mut self) {
+        /* A hint still being searched for is for a board that has changed. */
+        if self.hinting {
+            self.hint_solver.send(SolverRequest::Cancel);
+            self.hinting = false;
+        }
+        self.hint = None;
+    }
+}
+
+fn validate_tiles(tiles: &HashMap<Coord, Tile>, classes: &TileClasses) -> bool {
+    let mut has_even_tiles: HashMap<Tile, bool> = HashMap::new();
+    for class in tiles
+        .values()
+        .filter(|&&tile| tile != WALL)
+        .map(|&tile| classes.class(tile))
+    {
+        match has_even_tiles.contains_key(&class) {
+            true => has_even_tiles.insert(class, !has_even_tiles[&class]),
+            false => has_even_tiles.insert(class, false),
         };
     }
 
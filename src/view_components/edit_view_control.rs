@@ -3,22 +3,89 @@ use yew::prelude::*;
 use yew::virtual_dom::AttrValue;
 use yew::Properties;
 
+use super::super::catalogue::{create_bonus_tile_classes, FLOWERS};
 use super::super::components::Tile;
+use super::super::recognition::Recognition;
+use super::super::solver::classes::TileClasses;
+use super::super::solver::ordering::MoveOrderingKind;
+use super::super::solver::progress::SolverProgressReport;
+use super::super::solver::rules::RuleSet;
+use super::super::solver::solver::SolverError;
 use super::card::Card;
+use super::check_form_control_element::CheckFormControlElement;
+use super::download_link::DownloadLink;
 use super::edit_view_control_tile_selector::EditViewControlTileSelector;
+use super::file_form_control_element::FileFormControlElement;
 use super::input_form_control_element::InputFormControlElement;
+use super::screenshot_form_control_element::ScreenshotFormControlElement;
+use super::select_form_control_element::SelectFormControlElement;
+use super::share_link_button::ShareLinkButton;
+use super::textarea_form_control_element::TextareaFormControlElement;
 
 #[derive(Properties, PartialEq)]
 pub struct EditViewControlProps {
     pub rows: usize,
     pub cols: usize,
     pub tile: Option<Tile>,
+    pub rules: RuleSet,
+    #[prop_or_default]
+    pub ordering: MoveOrderingKind,
     pub submittable: bool,
+    #[prop_or(None)]
+    pub deadlock: Option<Vec<Tile>>,
+    #[prop_or(None)]
+    pub hint_failure: Option<SolverError>,
+    #[prop_or(false)]
+    pub hinting: bool,
+    #[prop_or(0)]
+    pub seed: u64,
+    #[prop_or(AttrValue::from(""))]
+    pub text: AttrValue,
+    #[prop_or(None)]
+    pub import_failure: Option<String>,
+    #[prop_or(AttrValue::from(""))]
+    pub board_json: AttrValue,
+    #[prop_or(AttrValue::from(""))]
+    pub share_fragment: AttrValue,
+    #[prop_or(0)]
+    pub n_uncertain: usize,
+    #[prop_or(false)]
+    pub solving: bool,
+    #[prop_or(None)]
+    pub progress: Option<SolverProgressReport>,
 
     pub on_rows_change: Callback<usize>,
     pub on_cols_change: Callback<usize>,
     pub on_tile_change: Callback<Option<Tile>>,
+    pub on_rules_change: Callback<RuleSet>,
+    pub on_ordering_change: Callback<MoveOrderingKind>,
+    pub on_seed_change: Callback<u64>,
+    pub on_text_change: Callback<AttrValue>,
     pub onsubmit: Callback<()>,
+    pub onhint: Callback<()>,
+    pub ongenerate: Callback<()>,
+    pub onimport: Callback<()>,
+    pub onexport: Callback<()>,
+    pub on_board_load: Callback<String>,
+    pub on_screenshot_load: Callback<Result<Recognition, String>>,
+    pub onclear: Callback<()>,
+    pub oncancel: Callback<()>,
+}
+
+const ORDERING_OPTIONS: [(&str, &str); 4] = [
+    ("fewest-remaining", "Fewest remaining tiles"),
+    ("fewest-blocking", "Fewest blocking tiles"),
+    ("shortest-path", "Shortest path"),
+    ("random", "Random"),
+];
+
+fn get_ordering_value(ordering: &MoveOrderingKind) -> &'static str {
+    match ordering {
+        MoveOrderingKind::FewestRemaining => ORDERING_OPTIONS[0].0,
+        MoveOrderingKind::FewestBlocking => ORDERING_OPTIONS[1].0,
+        MoveOrderingKind::ShortestPath => ORDERING_OPTIONS[2].0,
+        MoveOrderingKind::Random(_) => ORDERING_OPTIONS[3].0,
+    }
 }
 
 #[function_component(EditViewControl)]
@@ -26,7 +93,27 @@ pub fn edit_view_control(props: &EditViewControlProps) -> Html {
     let on_rows_change = props.on_rows_change.clone();
     let on_cols_change = props.on_cols_change.clone();
     let on_tile_change = props.on_tile_change.clone();
+    let on_turns_change = props.on_rules_change.clone();
+    let on_outside_change = props.on_rules_change.clone();
+    let on_gravity_change = props.on_rules_change.clone();
+    let on_classes_change = props.on_rules_change.clone();
+    /* Classes loaded from an archive may be neither preset, and are kept. */
+    let custom_classes = props.rules.classes != TileClasses::default()
+        && props.rules.classes != create_bonus_tile_classes();
+    let rules = props.rules;
+    let on_ordering_kind_change = props.on_ordering_change.clone();
+    let on_ordering_seed_change = props.on_ordering_change.clone();
     let onsubmit = props.onsubmit.clone();
+    let onhint = props.onhint.clone();
+    let on_seed_change = props.on_seed_change.clone();
+    let ongenerate = props.ongenerate.clone();
+    let on_text_change = props.on_text_change.clone();
+    let onimport = props.onimport.clone();
+    let onexport = props.onexport.clone();
+    let on_board_load = props.on_board_load.clone();
+    let on_screenshot_load = props.on_screenshot_load.clone();
+    let onclear = props.onclear.clone();
+    let oncancel = props.oncancel.clone();
 
     html! {
         <div>
@@ -58,6 +145,167 @@ pub fn edit_view_control(props: &EditViewControlProps) -> Html {
                     </div>
                 </div>
             </Card>
+            <Card header="Rules">
+                <InputFormControlElement
+                    label="Max turns"
+                    input_type="number"
+                    value={AttrValue::from(props.rules.max_turns.to_string())}
+                    onchange={Callback::from(move |v: AttrValue| {
+                        if let Ok(max_turns) = v.to_string().parse::<usize>() {
+                            on_turns_change.emit(RuleSet { max_turns, ..rules });
+                        }
+                    })}
+                />
+                <CheckFormControlElement
+                    label="Paths may go outside the board"
+                    checked={props.rules.allow_outside}
+                    onchange={Callback::from(move |allow_outside| {
+                        on_outside_change.emit(RuleSet { allow_outside, ..rules });
+                    })}
+                />
+                <CheckFormControlElement
+                    label="Tiles fall down after each removal"
+                    checked={props.rules.gravity}
+                    onchange={Callback::from(move |gravity| {
+                        on_gravity_change.emit(RuleSet { gravity, ..rules });
+                    })}
+                />
+                <CheckFormControlElement
+                    label={match custom_classes {
+                        true => "Tiles match by the classes of the loaded board",
+                        false => "Flowers and seasons match within their suit",
+                    }}
+                    checked={props.rules.classes.is_matching(FLOWERS[0], FLOWERS[1])}
+                    disabled={custom_classes}
+                    onchange={Callback::from(move |matching| {
+                        let classes = match matching {
+                            true => create_bonus_tile_classes(),
+                            false => TileClasses::default(),
+                        };
+                        on_classes_change.emit(RuleSet { classes, ..rules });
+                    })}
+                />
+            </Card>
+            <Card header="Search">
+                <SelectFormControlElement
+                    label="Move ordering"
+                    options={ORDERING_OPTIONS
+                        .iter()
+                        .map(|&(value, label)| (AttrValue::from(value), AttrValue::from(label)))
+                        .collect::<Vec<(AttrValue, AttrValue)>>()}
+                    value={AttrValue::from(get_ordering_value(&props.ordering))}
+                    onchange={Callback::from(move |v: AttrValue| {
+                        let ordering = match &*v.to_string() {
+                            "fewest-blocking" => MoveOrderingKind::FewestBlocking,
+                            "shortest-path" => MoveOrderingKind::ShortestPath,
+                            "random" => MoveOrderingKind::Random(0),
+                            _ => MoveOrderingKind::FewestRemaining,
+                        };
+                        on_ordering_kind_change.emit(ordering);
+                    })}
+                />
+                {match props.ordering {
+                    MoveOrderingKind::Random(seed) => html! {
+                        <InputFormControlElement
+                            label="Ordering seed"
+                            input_type="number"
+                            value={AttrValue::from(seed.to_string())}
+                            onchange={Callback::from(move |v: AttrValue| {
+                                if let Ok(seed) = v.to_string().parse::<u64>() {
+                                    on_ordering_seed_change.emit(MoveOrderingKind::Random(seed));
+                                }
+                            })}
+                        />
+                    },
+                    _ => html! {},
+                }}
+            </Card>
+            <Card header="Generator">
+                <InputFormControlElement
+                    label="Seed"
+                    input_type="number"
+                    value={AttrValue::from(props.seed.to_string())}
+                    onchange={Callback::from(move |v: AttrValue| {
+                        if let Ok(seed) = v.to_string().parse::<u64>() {
+                            on_seed_change.emit(seed);
+                        }
+                    })}
+                />
+                <button
+                    type="button"
+                    class="btn btn-outline-secondary"
+                    disabled={props.solving || !(props.rows * props.cols).is_multiple_of(2)}
+                    onclick={Callback::from(move |_| {
+                        ongenerate.emit(());
+                    })}
+                >
+                    { "Generate" }
+                </button>
+            </Card>
+            <Card header="Import / export">
+                <TextareaFormControlElement
+                    label="One row per line, such as `1m . E Rd`"
+                    value={props.text.clone()}
+                    onchange={Callback::from(move |text: AttrValue| {
+                        on_text_change.emit(text);
+                    })}
+                />
+                <button
+                    type="button"
+                    class="btn btn-outline-secondary"
+                    disabled={props.solving}
+                    onclick={Callback::from(move |_| {
+                        onimport.emit(());
+                    })}
+                >
+                    { "Import" }
+                </button>
+                <button
+                    type="button"
+                    class="btn btn-outline-secondary"
+                    onclick={Callback::from(move |_| {
+                        onexport.emit(());
+                    })}
+                >
+                    { "Export" }
+                </button>
+                <FileFormControlElement
+                    label="Board JSON"
+                    accept=".json,application/json"
+                    onload={Callback::from(move |json| {
+                        on_board_load.emit(json);
+                    })}
+                />
+                <DownloadLink
+                    label="Download JSON"
+                    filename="board.json"
+                    content={props.board_json.clone()}
+                />
+                <ShareLinkButton fragment={props.share_fragment.clone()} />
+                <ScreenshotFormControlElement
+                    label="Screenshot"
+                    disabled={props.solving}
+                    onload={Callback::from(move |result| {
+                        on_screenshot_load.emit(result);
+                    })}
+                />
+                {match props.n_uncertain {
+                    0 => html! {},
+                    n => html! {
+                        <div class="alert alert-info" role="alert">
+                            {format!("{} highlighted cell(s) were hard to recognize. Please check them.", n)}
+                        </div>
+                    },
+                }}
+                {match &props.import_failure {
+                    Some(err) => html! {
+                        <div class="alert alert-danger" role="alert">
+                            {err}
+                        </div>
+                    },
+                    None => html! {},
+                }}
+            </Card>
             <Card header="Tile selector">
                 <EditViewControlTileSelector
                     selected={props.tile}
@@ -66,17 +314,122 @@ pub fn edit_view_control(props: &EditViewControlProps) -> Html {
                     })}
                 />
             </Card>
+            {match &props.deadlock {
+                Some(tiles) => html! {
+                    <div class="alert alert-warning" role="alert">
+                        {format!(
+                            "{} tile kind(s) block each other, so this board cannot be solved.",
+                            tiles.len(),
+                        )}
+                    </div>
+                },
+                None => html! {},
+            }}
+            {match &props.hint_failure {
+                Some(SolverError::BudgetExceeded(_)) => html! {
+                    <div class="alert alert-info" role="alert">
+                        {"No hint was found within the search budget. The board may still be solvable."}
+                    </div>
+                },
+                Some(err) => html! {
+                    <div class="alert alert-warning" role="alert">
+                        {format!("No safe move was found: {}", err)}
+                    </div>
+                },
+                None => html! {},
+            }}
+            {match (props.solving, &props.progress) {
+                (true, Some(progress)) => html! {
+                    <div class="mb-3">
+                        <div class="progress">
+                            <div
+                                class="progress-bar"
+                                role="progressbar"
+                                style={format!("width: {:.1}%", progress.explored_ratio() * 100.0)}
+                            />
+                        </div>
+                        <small class="text-muted">
+                            {format!(
+                                "Best: {} of {} tiles removed, {:.0} nodes/s",
+                                progress.n_tiles - progress.n_best_remaining,
+                                progress.n_tiles,
+                                progress.nodes_per_second,
+                            )}
+                        </small>
+                    </div>
+                },
+                _ => html! {},
+            }}
             <div class="row">
-                    <button
-                        type="button"
-                        class="btn btn-primary"
-                        disabled={!props.submittable}
-                        onclick={Callback::from(move |_| {
-                            onsubmit.emit(());
-                        })}
-                    >
-                        { "Solve" }
-                    </button>
+                {match props.solving {
+                    true => html! {
+                        <button
+                            type="button"
+                            class="btn btn-secondary"
+                            onclick={Callback::from(move |_| {
+                                oncancel.emit(());
+                            })}
+                        >
+                            <span
+                                class="spinner-border spinner-border-sm"
+                                role="status"
+                                aria-hidden="true"
+                            />
+                            { " Cancel" }
+                        </button>
+                    },
+                    false => html! {
+                        <button
+                            type="button"
+                            class="btn btn-primary"
+                            disabled={!props.submittable}
+                            onclick={Callback::from(move |_| {
+                                onsubmit.emit(());
+                            })}
+                        >
+                            { "Solve" }
+                        </button>
+                    },
+                }}
+                {match props.solving {
+                    true => html! {},
+                    false => html! {
+                        <button
+                            type="button"
+                            class="btn btn-outline-primary"
+                            disabled={!props.submittable || props.hinting}
+                            onclick={Callback::from(move |_| {
+                                onhint.emit(());
+                            })}
+                        >
+                            {match props.hinting {
+                                true => html! {
+                                    <span
+                                        class="spinner-border spinner-border-sm"
+                                        role="status"
+                                        aria-hidden="true"
+                                    />
+                                },
+                                false => html! {},
+                            }}
+                            { " Hint" }
+                        </button>
+                    },
+                }}
+                {match props.solving {
+                    true => html! {},
+                    false => html! {
+                        <button
+                            type="button"
+                            class="btn btn-outline-danger"
+                            onclick={Callback::from(move |_| {
+                                onclear.emit(());
+                            })}
+                        >
+                            { "Clear" }
+                        </button>
+                    },
+                }}
             </div>
         </div>
     }
@@ -3,7 +3,8 @@ use yew::callback::Callback;
 use yew::prelude::*;
 use yew::Properties;
 
-use super::super::components::Tile;
+use super::super::catalogue::{get_tile_info, get_tiles_of_suit, SUITS};
+use super::super::components::{Tile, WALL};
 use super::edit_view_control_tile_selector_button::EditViewControlTileSelectorButton;
 use super::icons::tiles::create_all_tiles;
 
@@ -22,8 +23,10 @@ pub fn edit_view_control_tile_selector(props: &EditViewControlTileSelectorProps)
         use_effect_with_deps(
             move |_| {
                 spawn_local(async move {
-                    let fetched_images = create_all_tiles().await;
-                    images_clone.set(fetched_images);
+                    match create_all_tiles().await {
+                        Ok(fetched_images) => images_clone.set(fetched_images),
+                        Err(_) => log::warn!("Failed to load the tile images."),
+                    }
                 });
                 || ()
             },
@@ -31,29 +34,62 @@ pub fn edit_view_control_tile_selector(props: &EditViewControlTileSelectorProps)
         );
     }
 
+    let on_wall_change = props.onchange.clone();
+    let wall_selected = props.selected == Some(WALL);
+
     html! {
-        <div class="row">
+        <div>
         {
-            images.iter().enumerate().map(|(i, img)| {
-                let onchange = props.onchange.clone();
-                let tile = i as Tile;
-                let selected = props.selected == Some(tile);
+            SUITS.iter().map(|&suit| {
                 html! {
-                    <div class="col-2">
-                        <EditViewControlTileSelectorButton
-                            img={img.clone()}
-                            selected={selected}
-                            onclick={Callback::from(move |_| {
-                                onchange.emit(match selected {
-                                    true => None,
-                                    false => Some(tile),
-                                });
-                            })}
-                        />
+                    <div class="row">
+                    {
+                        get_tiles_of_suit(suit).into_iter().filter_map(|tile| {
+                            images.get(tile as usize).map(|img| (tile, img))
+                        }).map(|(tile, img)| {
+                            let onchange = props.onchange.clone();
+                            let selected = props.selected == Some(tile);
+                            html! {
+                                <div class="col-2">
+                                    <EditViewControlTileSelectorButton
+                                        img={img.clone()}
+                                        selected={selected}
+                                        title={get_tile_info(tile).map_or("", |info| info.name)}
+                                        onclick={Callback::from(move |_| {
+                                            onchange.emit(match selected {
+                                                true => None,
+                                                false => Some(tile),
+                                            });
+                                        })}
+                                    />
+                                </div>
+                            }
+                        }).collect::<Html>()
+                    }
                     </div>
                 }
             }).collect::<Html>()
         }
+        <div class="row">
+            <div class="col-2">
+                <div
+                    class={match wall_selected {
+                        true => "card bg-primary clickable",
+                        false => "card border-light clickable",
+                    }}
+                    onclick={Callback::from(move |_| {
+                        on_wall_change.emit(match wall_selected {
+                            true => None,
+                            false => Some(WALL),
+                        });
+                    })}
+                >
+                    <div class="card bg-secondary text-white tile-container text-center">
+                        { "Wall" }
+                    </div>
+                </div>
+            </div>
+        </div>
         </div>
     }
 }
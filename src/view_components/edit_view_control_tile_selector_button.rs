@@ -2,6 +2,7 @@ use web_sys::Element;
 use web_sys::Node;
 use yew::callback::Callback;
 use yew::prelude::*;
+use yew::virtual_dom::AttrValue;
 use yew::Properties;
 
 use super::icons::tiles::AsyncTileImage;
@@ -10,6 +11,8 @@ use super::icons::tiles::AsyncTileImage;
 pub struct EditViewControlTileSelectorButtonProps {
     pub img: AsyncTileImage,
     pub selected: bool,
+    #[prop_or(AttrValue::from(""))]
+    pub title: AttrValue,
 
     pub onclick: Callback<()>,
 }
@@ -29,6 +32,7 @@ pub fn edit_view_control_tile_selector_button(
                 true => "card bg-primary clickable",
                 false => "card border-light clickable",
             }}
+            title={props.title.clone()}
             onclick={Callback::from(move |_| onclick.emit(()))}
         >
             <div class="card bg-light tile-container">
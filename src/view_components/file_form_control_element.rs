@@ -0,0 +1,47 @@
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::Event;
+use web_sys::HtmlInputElement;
+use yew::callback::Callback;
+use yew::prelude::*;
+use yew::virtual_dom::AttrValue;
+use yew::Properties;
+
+#[derive(Properties, PartialEq)]
+pub struct FileFormControlElementProps {
+    pub label: AttrValue,
+    #[prop_or(AttrValue::from(""))]
+    pub accept: AttrValue,
+
+    pub onload: Callback<String>,
+}
+
+#[function_component(FileFormControlElement)]
+pub fn file_form_control_element(props: &FileFormControlElementProps) -> Html {
+    let onload = props.onload.clone();
+
+    html! {
+        <div>
+            <label class="form-label">
+                {&props.label}
+            </label>
+            <input
+                class="form-control"
+                type="file"
+                accept={props.accept.clone()}
+                onchange={Callback::from(move |e: Event| {
+                    let target = e.target().and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+                    let file = target.and_then(|input| input.files()).and_then(|files| files.get(0));
+                    if let Some(file) = file {
+                        let onload = onload.clone();
+                        spawn_local(async move {
+                            if let Some(text) = JsFuture::from(file.text()).await.ok().and_then(|v| v.as_string()) {
+                                onload.emit(text);
+                            }
+                        });
+                    }
+                })}
+            />
+        </div>
+    }
+}
@@ -1,16 +1,17 @@
 use std::rc::Rc;
 
 use base64;
-use futures::future::join_all;
+use futures::future::try_join_all;
 use js_sys::Promise;
 use wasm_bindgen::prelude::Closure;
 use wasm_bindgen::{JsCast, JsValue};
 use wasm_bindgen_futures::JsFuture;
 use web_sys::{Element, HtmlImageElement, Node};
 
+use super::super::super::catalogue::N_TILES;
 use super::super::super::components::Tile;
 
-const N_SVG_ICONS: usize = 34;
+const N_SVG_ICONS: usize = N_TILES;
 const SVG_ICON_STR: [&str; N_SVG_ICONS] = [
     include_str!("svg/1m.svg"),
     include_str!("svg/2m.svg"),
@@ -46,39 +47,53 @@ const SVG_ICON_STR: [&str; N_SVG_ICONS] = [
     include_str!("svg/red.svg"),
     include_str!("svg/green.svg"),
     include_str!("svg/white.svg"),
+    include_str!("svg/plum.svg"),
+    include_str!("svg/orchid.svg"),
+    include_str!("svg/chrysanthemum.svg"),
+    include_str!("svg/bamboo.svg"),
+    include_str!("svg/spring.svg"),
+    include_str!("svg/summer.svg"),
+    include_str!("svg/autumn.svg"),
+    include_str!("svg/winter.svg"),
 ];
 
-async fn create_image_from_svg(svg_str: &str) -> Result<Rc<HtmlImageElement>, JsValue> {
+pub async fn load_image(src: &str) -> Result<Rc<HtmlImageElement>, JsValue> {
     let img = HtmlImageElement::new()?;
-    let b64svg = base64::encode(svg_str);
-    img.set_src(&format!("data:image/svg+xml;base64,{}", b64svg));
+    img.set_src(src);
     let img_rc = Rc::new(img);
 
-    let promise = Promise::new(&mut |resolve, _| {
+    let promise = Promise::new(&mut |resolve, reject| {
         let img_clone = Rc::clone(&img_rc);
-        let closure = Closure::wrap(Box::new(move || {
+        let onload = Closure::wrap(Box::new(move || {
             let _ = resolve.call0(&JsValue::null());
         }) as Box<dyn FnMut()>);
-        img_clone.set_onload(Some(closure.as_ref().unchecked_ref()));
-        closure.forget();
+        let onerror = Closure::wrap(Box::new(move || {
+            let _ = reject.call0(&JsValue::null());
+        }) as Box<dyn FnMut()>);
+        img_clone.set_onload(Some(onload.as_ref().unchecked_ref()));
+        img_clone.set_onerror(Some(onerror.as_ref().unchecked_ref()));
+        onload.forget();
+        onerror.forget();
     });
 
     JsFuture::from(promise).await?;
     Ok(img_rc)
 }
 
+async fn create_image_from_svg(svg_str: &str) -> Result<Rc<HtmlImageElement>, JsValue> {
+    let b64svg = base64::encode(svg_str);
+    load_image(&format!("data:image/svg+xml;base64,{}", b64svg)).await
+}
+
 #[derive(PartialEq, Clone)]
 pub struct AsyncTileImage {
     image: Rc<HtmlImageElement>,
 }
 
 impl AsyncTileImage {
-    pub async fn new(tile: Tile) -> Self {
-        let future = create_image_from_svg(SVG_ICON_STR[tile as usize]);
-        let result = future.await;
-        Self {
-            image: result.unwrap(),
-        }
+    pub async fn new(tile: Tile) -> Result<Self, JsValue> {
+        let image = create_image_from_svg(SVG_ICON_STR[tile as usize]).await?;
+        Ok(Self { image })
     }
 
     pub fn as_ref(&self) -> &HtmlImageElement {
@@ -96,7 +111,7 @@ impl AsyncTileImage {
     }
 }
 
-pub async fn create_all_tiles() -> Vec<AsyncTileImage> {
+pub async fn create_all_tiles() -> Result<Vec<AsyncTileImage>, JsValue> {
     let promises = (0..N_SVG_ICONS).map(|tile| AsyncTileImage::new(tile as Tile));
-    join_all(promises).await
+    try_join_all(promises).await
 }
@@ -4,8 +4,14 @@ use yew::callback::Callback;
 use yew::prelude::*;
 use yew::Properties;
 
+use super::super::archive::SolutionDocument;
 use super::super::components::{Coord, Nodes, Tile};
-use super::super::solver::utils::pad_tilemap;
+use super::super::share::SharedBoard;
+use super::super::solver::ordering::MoveOrderingKind;
+use super::super::solver::progress::SolverProgressReport;
+use super::super::solver::rules::RuleSet;
+use super::super::solver::solver::{get_board_states, SolverError};
+use super::super::solver::utils::get_node_edges;
 use super::container_tab::ContainerTab;
 use super::edit_view::EditView;
 use super::result_view::ResultView;
@@ -24,12 +30,37 @@ pub struct MainContainerProps {
     pub rows: usize,
     pub cols: usize,
     pub tiles: HashMap<Coord, Tile>,
+    // The board in the editor, which is only solved when submitted.
+    pub draft: HashMap<Coord, Tile>,
     pub connections: Vec<Nodes>,
+    // The size and rules `tiles` were solved with, which the editor may have changed since.
+    pub solved_rows: usize,
+    pub solved_cols: usize,
+    #[prop_or_default]
+    pub solved_rules: RuleSet,
+    #[prop_or_default]
+    pub rules: RuleSet,
+    #[prop_or_default]
+    pub ordering: MoveOrderingKind,
+    #[prop_or(None)]
+    pub failure: Option<SolverError>,
+    #[prop_or(false)]
+    pub solving: bool,
+    #[prop_or(None)]
+    pub progress: Option<SolverProgressReport>,
+    #[prop_or(None)]
+    pub shared_step: Option<usize>,
 
     pub on_tab_change: Callback<MainContainerViewTarget>,
     pub on_rows_change: Callback<usize>,
     pub on_cols_change: Callback<usize>,
+    pub on_rules_change: Callback<RuleSet>,
+    pub on_ordering_change: Callback<MoveOrderingKind>,
+    pub on_draft_change: Callback<HashMap<Coord, Tile>>,
     pub on_editor_submit: Callback<HashMap<Coord, Tile>>,
+    pub on_editor_clear: Callback<()>,
+    pub on_solver_cancel: Callback<()>,
+    pub on_solution_load: Callback<SolutionDocument>,
 }
 
 #[function_component(MainContainer)]
@@ -38,7 +69,48 @@ pub fn main_container(props: &MainContainerProps) -> Html {
     let on_select_result_tab = props.on_tab_change.clone();
     let on_rows_change = props.on_rows_change.clone();
     let on_cols_change = props.on_cols_change.clone();
+    let on_rules_change = props.on_rules_change.clone();
+    let on_ordering_change = props.on_ordering_change.clone();
+    let padding = props.solved_rules.padding();
+    let states = match props.solved_rules.gravity {
+        true => get_board_states(
+            &props.tiles,
+            &[props.solved_cols, props.solved_rows],
+            &props.connections,
+            &props.solved_rules,
+        ),
+        false => Vec::new(),
+    };
+    let solution_json = SolutionDocument::new(
+        props.solved_rows,
+        props.solved_cols,
+        &props.tiles,
+        &props.solved_rules,
+        &props.connections,
+    )
+    .map(|solution| solution.to_json())
+    .unwrap_or_default();
+    /* Only a complete solution is shared, so that a step link shows it. */
+    let shared_board = SharedBoard {
+        rows: props.solved_rows,
+        cols: props.solved_cols,
+        tiles: props.tiles.clone(),
+        rules: props.solved_rules,
+        step: None,
+        moves: match props.failure {
+            Some(_) => Vec::new(),
+            None => props
+                .connections
+                .iter()
+                .map(|node| get_node_edges(node).map(|[x, y]| [x - padding, y - padding]))
+                .collect(),
+        },
+    };
+    let on_draft_change = props.on_draft_change.clone();
     let on_editor_submit = props.on_editor_submit.clone();
+    let on_editor_clear = props.on_editor_clear.clone();
+    let on_solver_cancel = props.on_solver_cancel.clone();
+    let on_solution_load = props.on_solution_load.clone();
 
     html! {
         <div>
@@ -65,26 +137,53 @@ pub fn main_container(props: &MainContainerProps) -> Html {
                             <EditView
                                 cols={props.cols}
                                 rows={props.rows}
-                                tiles={props.tiles.clone()}
+                                tiles={props.draft.clone()}
+                                rules={props.rules}
+                                ordering={props.ordering}
+                                solving={props.solving}
+                                progress={props.progress.clone()}
+                                on_tiles_change={Callback::from(move |tiles| {
+                                    on_draft_change.emit(tiles);
+                                })}
                                 onsubmit={Callback::from(move |tiles| {
                                     on_editor_submit.emit(tiles);
                                 })}
+                                onclear={Callback::from(move |_| {
+                                    on_editor_clear.emit(());
+                                })}
+                                oncancel={Callback::from(move |_| {
+                                    on_solver_cancel.emit(());
+                                })}
                                 on_rows_change={Callback::from(move |rows| {
                                     on_rows_change.emit(rows);
                                 })}
                                 on_cols_change={Callback::from(move |cols| {
                                     on_cols_change.emit(cols);
                                 })}
+                                on_rules_change={Callback::from(move |rules| {
+                                    on_rules_change.emit(rules);
+                                })}
+                                on_ordering_change={Callback::from(move |ordering| {
+                                    on_ordering_change.emit(ordering);
+                                })}
                             />
                         </div>
                     },
                     MainContainerViewTarget::ResultView => html! {
                         <div>
                             <ResultView
-                                cols={props.cols + 2}
-                                rows={props.rows + 2}
-                                tiles={pad_tilemap(&props.tiles)}
+                                cols={props.solved_cols + padding * 2}
+                                rows={props.solved_rows + padding * 2}
+                                tiles={props.solved_rules.pad_map(&props.tiles)}
                                 connections={props.connections.to_owned()}
+                                states={states}
+                                failure={props.failure.clone()}
+                                solution_json={solution_json}
+                                shared_board={shared_board}
+                                initial_selected={props.shared_step}
+                                on_solution_load={Callback::from(move |solution| {
+                                    on_solution_load.emit(solution);
+                                })}
                             />
                         </div>
                     },
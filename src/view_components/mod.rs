@@ -1,16 +1,23 @@
 pub mod main_container;
 
 mod card;
+mod check_form_control_element;
 mod container_tab;
+mod download_link;
 mod edit_map_canvas;
 mod edit_view;
 mod edit_view_control;
 mod edit_view_control_tile_selector;
 mod edit_view_control_tile_selector_button;
+mod file_form_control_element;
 mod icons;
 mod input_form_control_element;
 mod result_connection_list;
 mod result_connection_list_item;
 mod result_map_canvas;
 mod result_view;
+mod screenshot_form_control_element;
+mod select_form_control_element;
+mod share_link_button;
+mod textarea_form_control_element;
 mod tile_map_canvas;
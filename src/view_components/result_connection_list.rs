@@ -3,12 +3,15 @@ use yew::prelude::*;
 use yew::Properties;
 
 use super::super::components::{Nodes, TileMap};
+use super::super::solver::solver::get_step_tiles;
 use super::super::solver::utils::get_node_edges;
 use super::result_connection_list_item::ResultConnectionListItem;
 
 #[derive(Properties, PartialEq)]
 pub struct ResultConnectionListProps {
     pub tiles: TileMap,
+    // The board before each step, only given when tiles may fall.
+    pub states: Vec<TileMap>,
     pub connections: Vec<Nodes>,
     pub selected: Option<usize>,
 
@@ -17,21 +20,22 @@ pub struct ResultConnectionListProps {
 
 #[function_component(ResultConnectionList)]
 pub fn result_connection_list(props: &ResultConnectionListProps) -> Html {
+    let step_tiles = get_step_tiles(&props.tiles, &props.states, &props.connections);
     html! {
         <ol class="list-group">
-        {props.connections.iter().enumerate().map(|(i, nodes)| {
+        {props.connections.iter().zip(step_tiles).enumerate().filter_map(|(i, (nodes, tile))| {
             let onselect = props.onselect.clone();
             let [c1, c2] = get_node_edges(&nodes);
 
-            html!{
+            Some(html!{
                 <ResultConnectionListItem
                     selected={props.selected == Some(i)}
                     id={i}
                     coords={[c1, c2]}
-                    tile={props.tiles[&c1]}
+                    tile={tile?}
                     onclick={onselect}
                 />
-            }
+            })
        }).collect::<Html>()}
         </ol>
     }
@@ -35,9 +35,13 @@ pub fn result_connection_list_item(props: &ResultConnectionListItemProps) -> Htm
         use_effect_with_deps(
             move |_| {
                 spawn_local(async move {
-                    let fetched_image = AsyncTileImage::new(tile).await;
-                    fetched_image.resize(TILE_IMAGE_WIDTH, TILE_IMAGE_HEIGHT);
-                    image_clone.set(Some(fetched_image.node()));
+                    match AsyncTileImage::new(tile).await {
+                        Ok(fetched_image) => {
+                            fetched_image.resize(TILE_IMAGE_WIDTH, TILE_IMAGE_HEIGHT);
+                            image_clone.set(Some(fetched_image.node()));
+                        }
+                        Err(_) => log::warn!("Failed to load the tile image."),
+                    }
                 });
                 || ()
             },
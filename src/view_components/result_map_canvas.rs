@@ -11,6 +11,7 @@ use super::tile_map_canvas::TileMapCanvas;
 
 const TAKEN_TILE_COLOR: &str = "darkgray";
 const ACTIVE_TILE_COLOR: &str = "lightyellow";
+const REMAINING_TILE_COLOR: &str = "mistyrose";
 
 #[derive(Properties, PartialEq)]
 pub struct ResultMapCanvasProps {
@@ -19,6 +20,10 @@ pub struct ResultMapCanvasProps {
     pub tiles: TileMap,
 
     pub connections: Vec<Nodes>,
+    #[prop_or(Vec::new())]
+    pub states: Vec<TileMap>,
+    #[prop_or(HashMap::new())]
+    pub remaining: TileMap,
     pub cursor: Option<usize>,
 
     pub onselect: Callback<Option<usize>>,
@@ -40,18 +45,35 @@ pub fn result_map_canvas(props: &ResultMapCanvasProps) -> Html {
         }
     });
 
-    let mut backgrounds: HashMap<Coord, AttrValue> = HashMap::new();
+    let mut backgrounds: HashMap<Coord, AttrValue> = props
+        .remaining
+        .keys()
+        .map(|&coord| (coord, AttrValue::from(REMAINING_TILE_COLOR)))
+        .collect();
     let mut current_node: Option<Nodes> = None;
+    // Remaining tiles are given where they ended up after the last step.
+    let mut tiles = match (props.states.last(), props.remaining.is_empty()) {
+        (Some(state), false) => state.clone(),
+        _ => props.tiles.clone(),
+    };
     if let Some(cursor) = props.cursor {
+        /*
+         * With board states given, tiles may have moved since the start, so
+         * the board before the selected step is drawn instead of greying out
+         * the tiles already taken.
+         */
         let (taken, _) = props.connections.split_at(cursor);
-        backgrounds.extend(
-            taken
-                .iter()
-                .map(|node| get_node_edges(&node))
-                .flatten()
-                .map(|coord| (coord, AttrValue::from(TAKEN_TILE_COLOR)))
-                .collect::<HashMap<Coord, AttrValue>>(),
-        );
+        match props.states.get(cursor) {
+            Some(state) => tiles = state.clone(),
+            None => backgrounds.extend(
+                taken
+                    .iter()
+                    .map(|node| get_node_edges(&node))
+                    .flatten()
+                    .map(|coord| (coord, AttrValue::from(TAKEN_TILE_COLOR)))
+                    .collect::<HashMap<Coord, AttrValue>>(),
+            ),
+        }
         backgrounds.extend(
             get_node_edges(&props.connections[cursor])
                 .iter()
@@ -68,7 +90,7 @@ pub fn result_map_canvas(props: &ResultMapCanvasProps) -> Html {
             <TileMapCanvas
                 rows={props.rows}
                 cols={props.cols}
-                tiles={props.tiles.to_owned()}
+                tiles={tiles}
                 bg_color={backgrounds}
                 connection={current_node}
                 onclick={handle_select}
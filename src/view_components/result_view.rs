@@ -1,12 +1,20 @@
 use std::collections::HashMap;
 
 use yew::prelude::*;
+use yew::virtual_dom::AttrValue;
 use yew::Properties;
 
-use super::super::components::{Coord, Nodes, Tile};
+use super::super::archive::SolutionDocument;
+use super::super::components::{Coord, Nodes, Tile, TileMap};
+use super::super::share::SharedBoard;
+use super::super::solver::solver::SolverError;
 use super::card::Card;
-use super::result_map_canvas::ResultMapCanvas;
+use super::check_form_control_element::CheckFormControlElement;
+use super::download_link::DownloadLink;
+use super::file_form_control_element::FileFormControlElement;
 use super::result_connection_list::ResultConnectionList;
+use super::result_map_canvas::ResultMapCanvas;
+use super::share_link_button::ShareLinkButton;
 
 #[derive(Properties, PartialEq)]
 pub struct ResultViewProps {
@@ -15,27 +23,132 @@ pub struct ResultViewProps {
 
     pub tiles: HashMap<Coord, Tile>,
     pub connections: Vec<Nodes>,
+    #[prop_or(Vec::new())]
+    pub states: Vec<TileMap>,
+    #[prop_or(None)]
+    pub failure: Option<SolverError>,
+    // Empty when the connections cannot be saved.
+    #[prop_or(AttrValue::from(""))]
+    pub solution_json: AttrValue,
+    #[prop_or(None)]
+    pub shared_board: Option<SharedBoard>,
+    #[prop_or(None)]
+    pub initial_selected: Option<usize>,
+
+    pub on_solution_load: Callback<SolutionDocument>,
 }
 
 #[function_component(ResultView)]
 pub fn result_view(props: &ResultViewProps) -> Html {
-    let selected_index = use_state(|| None::<usize>);
+    let selected_index = {
+        let initial_selected = props
+            .initial_selected
+            .filter(|&idx| idx < props.connections.len());
+        use_state(move || initial_selected)
+    };
     let onselect = {
         let selected_index = selected_index.clone();
         Callback::from(move |idx| selected_index.set(idx))
     };
 
+    let load_failure = use_state(|| None::<String>);
+    let onload = {
+        let load_failure = load_failure.clone();
+        let on_solution_load = props.on_solution_load.clone();
+        Callback::from(move |json: String| match SolutionDocument::from_json(&json) {
+            Ok(solution) => {
+                load_failure.set(None);
+                on_solution_load.emit(solution);
+            }
+            Err(err) => load_failure.set(Some(err.to_string())),
+        })
+    };
+
+    let include_step = use_state(|| false);
+    let on_include_step_change = {
+        let include_step = include_step.clone();
+        Callback::from(move |checked| include_step.set(checked))
+    };
+    let share_fragment = match &props.shared_board {
+        Some(board) => SharedBoard {
+            step: (*selected_index).filter(|_| *include_step),
+            ..board.clone()
+        }
+        .to_fragment()
+        .unwrap_or_default(),
+        None => String::new(),
+    };
+
+    let remaining: TileMap = match props.failure.as_ref().and_then(|err| err.diagnostics()) {
+        Some(diagnostics) => diagnostics.remaining.clone(),
+        None => HashMap::new(),
+    };
+
     html! {
         <div class="row">
+            {match props.failure.as_ref() {
+                Some(err) => html! {
+                    <div class="col-12">
+                        <div class="alert alert-warning" role="alert">
+                            <div>{err.to_string()}</div>
+                            {match err.diagnostics() {
+                                Some(diagnostics) => html! {
+                                    <div>
+                                        {format!(
+                                            "Best partial solution removes {} pairs, {} tiles left.",
+                                            diagnostics.deepest.len(),
+                                            diagnostics.remaining.len(),
+                                        )}
+                                    </div>
+                                },
+                                None => html! {},
+                            }}
+                        </div>
+                    </div>
+                },
+                None => html! {},
+            }}
             <div class="col-12 col-lg-4">
                 <Card header={"Solving steps"}>
                     <ResultConnectionList
                         tiles={props.tiles.to_owned()}
+                        states={props.states.to_owned()}
                         connections={props.connections.to_owned()}
                         selected={*selected_index}
                         onselect={onselect.to_owned()}
                     />
                 </Card>
+                <Card header={"Import / export"}>
+                    <FileFormControlElement
+                        label="Solution JSON"
+                        accept=".json,application/json"
+                        onload={onload}
+                    />
+                    {match props.connections.is_empty() || props.solution_json.is_empty() {
+                        true => html! {},
+                        false => html! {
+                            <DownloadLink
+                                label="Download JSON"
+                                filename="solution.json"
+                                content={props.solution_json.clone()}
+                            />
+                        },
+                    }}
+                    <CheckFormControlElement
+                        label="Include the selected step"
+                        checked={*include_step}
+                        onchange={on_include_step_change}
+                    />
+                    <ShareLinkButton fragment={share_fragment} />
+                    {match (*load_failure).as_ref() {
+                        Some(err) => html! {
+                            <div class="alert alert-danger" role="alert">
+                                {err}
+                            </div>
+                        },
+                        None => html! {},
+                    }}
+                </Card>
             </div>
             <div class="col-12 col-lg-8">
                 <Card>
@@ -44,6 +157,8 @@ pub fn result_view(props: &ResultViewProps) -> Html {
                         cols={props.cols}
                         tiles={props.tiles.to_owned()}
                         connections={props.connections.to_owned()}
+                        states={props.states.to_owned()}
+                        remaining={remaining}
                         cursor={*selected_index}
                         onselect={onselect.to_owned()}
                     />
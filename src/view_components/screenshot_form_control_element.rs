@@ -0,0 +1,148 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use js_sys::Uint8Array;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{
+    CanvasRenderingContext2d, Event, File, HtmlCanvasElement, HtmlImageElement, HtmlInputElement,
+};
+use yew::callback::Callback;
+use yew::prelude::*;
+use yew::virtual_dom::AttrValue;
+use yew::Properties;
+
+use super::super::components::Tile;
+use super::super::recognition::{recognize_board, GrayImage, Recognition, TemplateSet};
+use super::icons::tiles::{create_all_tiles, load_image};
+
+const TEMPLATE_WIDTH: usize = 24;
+const TEMPLATE_HEIGHT: usize = 30;
+const TEMPLATE_RENDER_WIDTH: u32 = 80;
+const TEMPLATE_RENDER_HEIGHT: u32 = 100;
+
+thread_local! {
+    // The templates are rendered from the bundled icons on the first import.
+    static TEMPLATES: RefCell<Option<Rc<TemplateSet>>> = const { RefCell::new(None) };
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ScreenshotFormControlElementProps {
+    pub label: AttrValue,
+    #[prop_or(false)]
+    pub disabled: bool,
+
+    pub onload: Callback<Result<Recognition, String>>,
+}
+
+fn read_pixels(image: &HtmlImageElement, width: u32, height: u32) -> Result<GrayImage, JsValue> {
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| JsValue::from_str("No document"))?;
+    let canvas = document
+        .create_element("canvas")?
+        .dyn_into::<HtmlCanvasElement>()?;
+    canvas.set_width(width);
+    canvas.set_height(height);
+    let context = canvas
+        .get_context("2d")?
+        .ok_or_else(|| JsValue::from_str("No 2d context"))?
+        .dyn_into::<CanvasRenderingContext2d>()?;
+    let (w, h) = (width as f64, height as f64);
+    context.draw_image_with_html_image_element_and_dw_and_dh(image, 0., 0., w, h)?;
+    let data = context.get_image_data(0., 0., w, h)?.data();
+    GrayImage::from_rgba(width as usize, height as usize, &data)
+        .ok_or_else(|| JsValue::from_str("Unexpected image data"))
+}
+
+async fn create_templates() -> Result<TemplateSet, JsValue> {
+    /*
+     * The bundled icons are rendered at the size the board is drawn at, and
+     * shrunk to the template size by `TemplateSet::add`.
+     */
+    let mut templates = TemplateSet::new(TEMPLATE_WIDTH, TEMPLATE_HEIGHT);
+    for (tile, image) in create_all_tiles().await?.iter().enumerate() {
+        let pixels = read_pixels(
+            image.as_ref(),
+            TEMPLATE_RENDER_WIDTH,
+            TEMPLATE_RENDER_HEIGHT,
+        )?;
+        templates.add(tile as Tile, &pixels);
+    }
+    Ok(templates)
+}
+
+async fn get_templates() -> Result<Rc<TemplateSet>, JsValue> {
+    if let Some(templates) = TEMPLATES.with(|cache| cache.borrow().clone()) {
+        return Ok(templates);
+    }
+    let templates = Rc::new(create_templates().await?);
+    TEMPLATES.with(|cache| *cache.borrow_mut() = Some(Rc::clone(&templates)));
+    Ok(templates)
+}
+
+async fn recognize_file(file: File) -> Result<Recognition, String> {
+    let buffer = JsFuture::from(file.array_buffer())
+        .await
+        .map_err(|_| String::from("Failed to read the file"))?;
+    let bytes = Uint8Array::new(&buffer).to_vec();
+    let url = format!("data:{};base64,{}", file.type_(), base64::encode(bytes));
+    let image = load_image(&url)
+        .await
+        .map_err(|_| String::from("The file is not an image"))?;
+    let screenshot = read_pixels(&image, image.natural_width(), image.natural_height())
+        .map_err(|_| String::from("Failed to read the image"))?;
+    let templates = get_templates()
+        .await
+        .map_err(|_| String::from("Failed to load the tile images"))?;
+    recognize_board(&screenshot, &templates)
+        .ok_or_else(|| String::from("No tiles were found in the screenshot"))
+}
+
+#[function_component(ScreenshotFormControlElement)]
+pub fn screenshot_form_control_element(props: &ScreenshotFormControlElementProps) -> Html {
+    let recognizing = use_state(|| false);
+    let onchange = {
+        let recognizing = recognizing.clone();
+        let onload = props.onload.clone();
+        Callback::from(move |e: Event| {
+            let target = e
+                .target()
+                .and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            let file = target
+                .and_then(|input| input.files())
+                .and_then(|files| files.get(0));
+            if let Some(file) = file {
+                let recognizing = recognizing.clone();
+                let onload = onload.clone();
+                recognizing.set(true);
+                spawn_local(async move {
+                    let result = recognize_file(file).await;
+                    recognizing.set(false);
+                    onload.emit(result);
+                });
+            }
+        })
+    };
+
+    html! {
+        <div>
+            <label class="form-label">
+                {&props.label}
+            </label>
+            <input
+                class="form-control"
+                type="file"
+                accept="image/png,image/jpeg"
+                disabled={props.disabled || *recognizing}
+                onchange={onchange}
+            />
+            {match *recognizing {
+                true => html! {
+                    <small class="text-muted">{"Recognizing tiles..."}</small>
+                },
+                false => html! {},
+            }}
+        </div>
+    }
+}
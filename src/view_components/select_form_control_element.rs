@@ -0,0 +1,45 @@
+use wasm_bindgen::JsCast;
+use web_sys::Event;
+use web_sys::HtmlSelectElement;
+use yew::callback::Callback;
+use yew::prelude::*;
+use yew::virtual_dom::AttrValue;
+use yew::Properties;
+
+#[derive(Properties, PartialEq)]
+pub struct SelectFormControlElementProps {
+    pub label: AttrValue,
+    // (value, label) of each option.
+    pub options: Vec<(AttrValue, AttrValue)>,
+    pub value: AttrValue,
+
+    pub onchange: Callback<AttrValue>,
+}
+
+#[function_component(SelectFormControlElement)]
+pub fn select_form_control_element(props: &SelectFormControlElementProps) -> Html {
+    let onchange = props.onchange.clone();
+
+    html! {
+        <div>
+            <label class="form-label">
+                {&props.label}
+            </label>
+            <select
+                class="form-select"
+                onchange={Callback::from(move |e: Event| {
+                    let target = e.target().and_then(|t| t.dyn_into::<HtmlSelectElement>().ok());
+                    if let Some(select) = target {
+                        onchange.emit(AttrValue::from(select.value()));
+                    }
+                })}
+            >
+                {for props.options.iter().map(|(value, label)| html! {
+                    <option value={value.clone()} selected={*value == props.value}>
+                        {label}
+                    </option>
+                })}
+            </select>
+        </div>
+    }
+}
@@ -0,0 +1,94 @@
+use js_sys::{Function, Promise, Reflect};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use yew::callback::Callback;
+use yew::prelude::*;
+use yew::virtual_dom::AttrValue;
+use yew::Properties;
+
+#[derive(Properties, PartialEq)]
+pub struct ShareLinkButtonProps {
+    #[prop_or(AttrValue::from("Copy share link"))]
+    pub label: AttrValue,
+    // An empty fragment disables the button.
+    pub fragment: AttrValue,
+}
+
+fn get_share_link(fragment: &str) -> Option<String> {
+    let href = web_sys::window()?.location().href().ok()?;
+    let base = match href.split_once('#') {
+        Some((base, _)) => base,
+        None => &href,
+    };
+    Some(format!("{}#{}", base, fragment))
+}
+
+fn write_to_clipboard(text: &str) -> Option<Promise> {
+    /*
+     * `Navigator::clipboard` is behind the unstable web-sys APIs, so the
+     * Clipboard API is called through reflection.
+     */
+    let navigator = web_sys::window()?.navigator();
+    let clipboard = Reflect::get(&navigator, &JsValue::from("clipboard")).ok()?;
+    let write_text = Reflect::get(&clipboard, &JsValue::from("writeText"))
+        .ok()?
+        .dyn_into::<Function>()
+        .ok()?;
+    write_text
+        .call1(&clipboard, &JsValue::from(text))
+        .ok()?
+        .dyn_into::<Promise>()
+        .ok()
+}
+
+#[function_component(ShareLinkButton)]
+pub fn share_link_button(props: &ShareLinkButtonProps) -> Html {
+    let status = use_state(|| None::<Result<(), String>>);
+    let onclick = {
+        let status = status.clone();
+        let fragment = props.fragment.clone();
+        Callback::from(move |_| {
+            let link = match get_share_link(&fragment) {
+                Some(link) => link,
+                None => return,
+            };
+            let status = status.clone();
+            match write_to_clipboard(&link) {
+                Some(promise) => spawn_local(async move {
+                    match JsFuture::from(promise).await {
+                        Ok(_) => status.set(Some(Ok(()))),
+                        Err(_) => status.set(Some(Err(link))),
+                    }
+                }),
+                None => status.set(Some(Err(link))),
+            }
+        })
+    };
+
+    html! {
+        <div>
+            <button
+                type="button"
+                class="btn btn-outline-secondary"
+                disabled={props.fragment.is_empty()}
+                onclick={onclick}
+            >
+                {&props.label}
+            </button>
+            {match (*status).as_ref() {
+                Some(Ok(())) => html! {
+                    <span class="form-text ms-2">{"Link copied"}</span>
+                },
+                Some(Err(link)) => html! {
+                    <input
+                        class="form-control form-control-sm"
+                        type="text"
+                        readonly=true
+                        value={link.clone()}
+                    />
+                },
+                None => html! {},
+            }}
+        </div>
+    }
+}
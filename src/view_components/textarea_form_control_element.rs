@@ -0,0 +1,41 @@
+use wasm_bindgen::JsCast;
+use web_sys::Event;
+use web_sys::HtmlTextAreaElement;
+use yew::callback::Callback;
+use yew::prelude::*;
+use yew::virtual_dom::AttrValue;
+use yew::Properties;
+
+#[derive(Properties, PartialEq)]
+pub struct TextareaFormControlElementProps {
+    pub label: AttrValue,
+    pub value: AttrValue,
+    #[prop_or(8)]
+    pub rows: usize,
+
+    pub onchange: Callback<AttrValue>,
+}
+
+#[function_component(TextareaFormControlElement)]
+pub fn textarea_form_control_element(props: &TextareaFormControlElementProps) -> Html {
+    let onchange = props.onchange.clone();
+
+    html! {
+        <div>
+            <label class="form-label">
+                {&props.label}
+            </label>
+            <textarea
+                class="form-control font-monospace"
+                rows={props.rows.to_string()}
+                value={props.value.clone()}
+                onchange={Callback::from(move |e: Event| {
+                    let target = e.target().and_then(|t| t.dyn_into::<HtmlTextAreaElement>().ok());
+                    if let Some(textarea) = target {
+                        onchange.emit(AttrValue::from(textarea.value()));
+                    }
+                })}
+            />
+        </div>
+    }
+}
@@ -8,7 +8,7 @@ use yew::prelude::*;
 use yew::virtual_dom::AttrValue;
 use yew::{NodeRef, Properties};
 
-use super::super::components::{Coord, CoordElement, Nodes, Tile, TileMap};
+use super::super::components::{Coord, CoordElement, Nodes, Tile, TileMap, WALL};
 use super::icons::tiles::{create_all_tiles, AsyncTileImage};
 
 const TILE_WIDTH: usize = 80;
@@ -16,6 +16,7 @@ const TILE_HEIGHT: usize = 100;
 const MAP_MARGIN: usize = 5;
 const DEFAULT_BG_COLOR: &str = "white";
 const BLANK_BG_COLOR: &str = "gainsboro";
+const WALL_BG_COLOR: &str = "dimgray";
 const GRID_COLOR: &str = "gray";
 const GRID_WIDTH: f64 = 1.0;
 const NODE_COLOR: &str = "orange";
@@ -102,8 +103,10 @@ impl Component for TileMapCanvas {
                 .link()
                 .callback(|provider| Self::Message::ImageLoaded(provider));
             spawn_local(async move {
-                let fetched_images = create_all_tiles().await;
-                onload.emit(fetched_images);
+                match create_all_tiles().await {
+                    Ok(fetched_images) => onload.emit(fetched_images),
+                    Err(_) => log::warn!("Failed to load the tile images."),
+                }
             });
         } else {
             self.reset_map(ctx);
@@ -168,9 +171,13 @@ impl TileMapCanvas {
             ctx.props().tile_width as f64,
             ctx.props().tile_height as f64,
         );
-        for (&[xpos, ypos], _) in &ctx.props().tiles {
+        for (&[xpos, ypos], &tile) in &ctx.props().tiles {
             let (x, y) = (self.tile_left(ctx, xpos), self.tile_top(ctx, ypos));
-            self.draw_background(&context, x, y, w, h, DEFAULT_BG_COLOR);
+            let color = match tile {
+                WALL => WALL_BG_COLOR,
+                _ => DEFAULT_BG_COLOR,
+            };
+            self.draw_background(&context, x, y, w, h, color);
         }
     }
 
@@ -272,7 +279,7 @@ impl TileMapCanvas {
             ctx.props().tile_height as f64,
         );
 
-        for (&[xpos, ypos], &tile) in &ctx.props().tiles {
+        for (&[xpos, ypos], &tile) in ctx.props().tiles.iter().filter(|(_, &t)| t != WALL) {
             let (x, y) = (self.tile_left(ctx, xpos), self.tile_top(ctx, ypos));
             self.draw_image(&context, x, y, w, h, tile);
         }
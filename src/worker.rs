@@ -0,0 +1,153 @@
+use gloo_timers::future::TimeoutFuture;
+use gloo_worker::{HandlerId, Worker, WorkerScope};
+use serde::{Deserialize, Serialize};
+
+use super::components::{Nodes, TileMap};
+use super::solver::connect::PathSelection;
+use super::solver::ordering::{MoveOrdering, MoveOrderingKind};
+use super::solver::progress::SolverProgressReport;
+use super::solver::rules::RuleSet;
+use super::solver::solver::{SichuanSolver, SolverError, SolverProgress};
+
+/* Relative, so the worker is found when the app is served below a `--public-url`. */
+pub const SOLVER_WORKER_PATH: &str = "./worker.js";
+const SOLVER_STEPS_PER_TICK: usize = 200;
+const SOLVER_PROGRESS_INTERVAL: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SolveRequest {
+    pub generation: usize,
+    pub map: TileMap,
+    pub size: [usize; 2],
+    pub rules: RuleSet,
+    pub path_selection: PathSelection,
+    pub ordering: MoveOrderingKind,
+    pub max_steps: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SolverRequest {
+    Solve(Box<SolveRequest>),
+    Cancel,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SolverOutput {
+    Progress(SolverProgressReport),
+    // The request is echoed, as the editor may have changed since it was sent.
+    Finished(Box<SolveRequest>, Result<Vec<Nodes>, SolverError>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SolverResponse {
+    pub generation: usize,
+    pub output: SolverOutput,
+}
+
+pub enum SolverWorkerMsg {
+    Stepped(usize),
+}
+
+struct SolverJob {
+    id: HandlerId,
+    request: Box<SolveRequest>,
+    solver: SichuanSolver<Box<dyn MoveOrdering>>,
+}
+
+pub struct SolverWorker {
+    job: Option<SolverJob>,
+}
+
+impl SolverWorker {
+    fn schedule_step(scope: &WorkerScope<Self>, generation: usize) {
+        /*
+         * A timer rather than `send_message` hands control back to the event
+         * loop between chunks, so a `Cancel` sent meanwhile gets through.
+         */
+        scope.send_future(async move {
+            TimeoutFuture::new(0).await;
+            SolverWorkerMsg::Stepped(generation)
+        });
+    }
+}
+
+impl Worker for SolverWorker {
+    type Message = SolverWorkerMsg;
+    type Input = SolverRequest;
+    type Output = SolverResponse;
+
+    fn create(_scope: &WorkerScope<Self>) -> Self {
+        Self { job: None }
+    }
+
+    fn update(&mut self, scope: &WorkerScope<Self>, msg: Self::Message) {
+        match msg {
+            Self::Message::Stepped(generation) => {
+                let progress = match self.job.as_mut() {
+                    Some(job) if job.request.generation == generation => {
+                        job.solver.solve_for(SOLVER_STEPS_PER_TICK)
+                    }
+                    _ => return,
+                };
+                let result = match progress {
+                    SolverProgress::Pending => {
+                        Self::schedule_step(scope, generation);
+                        return;
+                    }
+                    SolverProgress::Solved => Ok(self
+                        .job
+                        .as_ref()
+                        .map_or(Vec::new(), |job| job.solver.result())),
+                    SolverProgress::Failed(err) => Err(err),
+                };
+                if let Some(job) = self.job.take() {
+                    scope.respond(
+                        job.id,
+                        SolverResponse {
+                            generation,
+                            output: SolverOutput::Finished(job.request, result),
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    fn received(&mut self, scope: &WorkerScope<Self>, msg: Self::Input, id: HandlerId) {
+        match msg {
+            SolverRequest::Solve(request) => {
+                let mut solver = SichuanSolver::with_ordering(
+                    &request.map,
+                    &request.size,
+                    &request.rules,
+                    request.ordering.create(),
+                );
+                solver.set_max_steps(request.max_steps);
+                solver.set_path_selection(request.path_selection);
+
+                let (progress_scope, generation) = (scope.clone(), request.generation);
+                solver.set_progress_observer(
+                    Box::new(move |report: &SolverProgressReport| {
+                        progress_scope.respond(
+                            id,
+                            SolverResponse {
+                                generation,
+                                output: SolverOutput::Progress(report.clone()),
+                            },
+                        );
+                    }),
+                    SOLVER_PROGRESS_INTERVAL,
+                );
+                self.job = Some(SolverJob {
+                    id,
+                    request,
+                    solver,
+                });
+                Self::schedule_step(scope, generation);
+            }
+            SolverRequest::Cancel => {
+                self.job = None;
+            }
+        }
+    }
+}